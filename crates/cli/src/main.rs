@@ -0,0 +1,742 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, fs, path::{Path, PathBuf}, process::ExitCode};
+
+use parselist::{
+    builds::{self, BuildOptions},
+    data::{self, IngestMode, IngestReport, MasterTable, boss_to_boss_name, partition_to_name},
+    dates::{Date, DateRange},
+    filter::{DpsType, RowFilter},
+    format,
+    ingest::{self, IngestConfig},
+    query::{GroupBy, Measure, Query},
+};
+
+const USAGE: &str = "\
+usage: ingest <command> [options]
+
+commands:
+  build      parse the input files and write the master table
+  merge      add the rows in the input files to an existing master table
+  validate   parse the input files and report every problem without writing anything,
+             including set ids that set_data.csv and set_ids.csv disagree on
+  inspect    print rows from an existing master table
+  stats      print row counts per partition and boss for an existing master table
+  slots      print the most worn sets in each gear slot of an existing master table
+  bars       print which bar skills are slotted on and the most common ultimate pairs
+  classes    print the share of each class and class combination, and how many parses subclass
+  builds     print the most common builds, rows grouped by main class and set combination
+  companions print the skills most often slotted alongside one skill, by lift over their usual usage
+  pairs      print the sets most often worn together and the most common full set combinations
+  query      group the rows by one field and print one measure for each group
+  migrate    rewrite an existing master table in the current file version
+
+build / merge / validate options:
+  --total <csv|dir>   total dps rankings (default data/total_dps.csv)
+  --boss <csv|dir>    single target rankings (default data/boss_dps.csv)
+                      a directory is read as the scraper's zone_*/boss_*/partition_*.csv tree
+  --set-data <csv>    set id to name table (default data/set_data.csv)
+  --set-ids <csv>     item id to set id table (default data/set_ids.csv)
+  --skills <json>     skill metadata (default data/skills.json)
+  --extra-skills <list>
+                      comma separated files to fill in skills missing from --skills, either
+                      in the skills.json layout or a UESP style minedSkills CSV export
+  --names <dir>       LibCustomNames addon folder, or one of its names folders (skipped when not given)
+  --regions <list>    comma separated server folders to read names from (default PC), e.g. PC,PC-EU
+  --gear-patch <csv>  write suggested set_ids.csv lines for gear ids that matched a set by name
+
+build / merge / migrate options:
+  --lenient           skip bad lines and print a summary instead of stopping
+  --out <bin>         output table (default data/master_table.bin, or --table for merge/migrate)
+  --json <json>       readable copy of the output (default data/master_table.json)
+  --no-json           don't write the readable copy
+
+merge / inspect / stats / slots / bars / classes / builds / companions / pairs / query / migrate options:
+  --table <bin>       master table to read (default data/master_table.bin)
+
+inspect options:
+  --player <name>     only show rows for this @name
+  --partition <id>    only show rows from this partition
+  --limit <n>         number of rows to print (default 20)
+
+slots / bars / classes / builds / companions / pairs / query options:
+  --partition <id>    only count rows from this partition
+  --boss-id <id>      only count rows from this boss
+  --zone <id>         only count rows from this trial
+  --dps <st|aoe>      only count single target or total dps rankings
+  --rankings <a-b>    only count rankings a to b, e.g. 1-10
+  --from <date>       only count parses on or after this day, YYYY-MM-DD
+  --to <date>         only count parses on or before this day, YYYY-MM-DD
+
+slots options:
+  --limit <n>         number of sets to print per slot (default 5)
+
+bars options:
+  --limit <n>         number of skills and ultimate pairs to print (default 20)
+
+classes options:
+  --limit <n>         number of class combinations to print (default 20)
+
+builds options:
+  --similarity <x>    also split builds into skill bars at least this Jaccard similar, 0 to 1
+  --min-rows <n>      leave out builds with fewer rows
+  --limit <n>         number of builds to print (default 20)
+
+companions options:
+  --skill <id|name>   skill to look up, by id or name
+  --min-rows <n>      leave out companions slotted alongside it on fewer rows (default 10)
+  --limit <n>         number of companions to print (default 20)
+
+pairs options:
+  --limit <n>         number of set pairs and combinations to print (default 20)
+
+query options:
+  --group <field>     skill, skill-slot, set, class, boss, partition or player
+                      (default skill); skill-slot counts a skill once per slot
+  --measure <m>       count, share, normalised, dps or rank (default share)
+  --min-rows <n>      leave out groups with fewer rows
+  --limit <n>         number of groups to print (default 20)
+";
+
+struct Args {
+    options: HashMap<String, String>,
+    switches: Vec<String>,
+}
+
+impl Args {
+    fn parse(raw: &[String]) -> Result<Args, String> {
+        let mut options = HashMap::new();
+        let mut switches = Vec::new();
+        let mut iter = raw.iter();
+
+        while let Some(arg) = iter.next() {
+            let Some(key) = arg.strip_prefix("--") else {
+                return Err(format!("unexpected argument '{}'", arg));
+            };
+            if key == "no-json" || key == "lenient" {
+                switches.push(key.to_string());
+                continue;
+            }
+            let value = iter.next().ok_or_else(|| format!("missing value for --{}", key))?;
+            options.insert(key.to_string(), value.clone());
+        }
+
+        Ok(Args { options, switches })
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        self.options.get(key).map(PathBuf::from)
+    }
+
+    fn number<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        match self.options.get(key) {
+            Some(v) => v.parse().map(Some).map_err(|_| format!("invalid value '{}' for --{}", v, key)),
+            None => Ok(None),
+        }
+    }
+
+    fn has(&self, switch: &str) -> bool {
+        self.switches.iter().any(|s| s == switch)
+    }
+
+    fn ingest_config(&self) -> IngestConfig {
+        let default = IngestConfig::default();
+        IngestConfig {
+            total_dps: self.path("total").unwrap_or(default.total_dps),
+            boss_dps: self.path("boss").unwrap_or(default.boss_dps),
+            set_data: self.path("set-data").unwrap_or(default.set_data),
+            set_ids: self.path("set-ids").unwrap_or(default.set_ids),
+            skills: self.path("skills").unwrap_or(default.skills),
+            extra_skills: match self.options.get("extra-skills") {
+                Some(list) => list.split(',').map(str::trim).filter(|p| !p.is_empty()).map(PathBuf::from).collect(),
+                None => default.extra_skills,
+            },
+            names_dir: self.path("names"),
+            name_regions: match self.options.get("regions") {
+                Some(list) => list.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect(),
+                None => default.name_regions,
+            },
+            mode: if self.has("lenient") { IngestMode::Lenient } else { IngestMode::Strict },
+        }
+    }
+
+    fn row_filter(&self) -> Result<RowFilter, String> {
+        let dps_type = match self.options.get("dps").map(String::as_str) {
+            None => DpsType::Both,
+            Some("st") => DpsType::SingleTarget,
+            Some("aoe") => DpsType::Aoe,
+            Some(v) => return Err(format!("invalid value '{}' for --dps, expected st or aoe", v)),
+        };
+        let rankings = match self.options.get("rankings") {
+            Some(v) => {
+                let parsed = v.split_once('-').and_then(|(a, b)| Some((a.trim().parse().ok()?, b.trim().parse().ok()?)));
+                Some(parsed.ok_or_else(|| format!("invalid value '{}' for --rankings, expected e.g. 1-10", v))?)
+            }
+            None => None,
+        };
+        let date = |key: &str| -> Result<Option<Date>, String> {
+            self.options.get(key).map(|v| v.parse().map_err(|e| format!("{} for --{}", e, key))).transpose()
+        };
+
+        Ok(RowFilter {
+            partitions: self.number("partition")?.into_iter().collect(),
+            bosses: self.number("boss-id")?.into_iter().collect(),
+            zones: self.number("zone")?.into_iter().collect(),
+            dps_type,
+            rankings,
+            dates: DateRange { start: date("from")?, end: date("to")? },
+        })
+    }
+
+    fn table_path(&self) -> PathBuf {
+        self.path("table").unwrap_or_else(|| PathBuf::from("data/master_table.bin"))
+    }
+
+    fn table(&self) -> Result<MasterTable, String> {
+        read_table(&self.table_path())
+    }
+}
+
+fn read_table(path: &Path) -> Result<MasterTable, String> {
+    let bytes = fs::read(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    data::decode_master_table(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn print_summary(master: &MasterTable) {
+    println!(
+        "Rows: {}, Players: {}, Skills: {}, Sets: {}",
+        master.rows.len(), master.players.len(), master.skills.len(), master.sets.len()
+    );
+}
+
+const REPORT_LIMIT: usize = 20;
+
+fn print_unmapped_gear(report: &IngestReport, master: &MasterTable) {
+    if report.unmapped_gear.is_empty() {
+        return;
+    }
+
+    let matched = report.unmapped_gear.iter().filter(|g| g.suggested_set.is_some()).count();
+    eprintln!(
+        "{} gear ids missing from the set ids, {} matched a set by name",
+        report.unmapped_gear.len(), matched
+    );
+    for gear in report.unmapped_gear.iter().take(REPORT_LIMIT) {
+        let set = match gear.suggested_set {
+            Some(id) => master.set(id).map(|s| s.name.as_str()).unwrap_or("?"),
+            None => "no match",
+        };
+        eprintln!("  {} {} (worn {} times) -> {}", gear.item_id, gear.name, gear.count, set);
+    }
+    if report.unmapped_gear.len() > REPORT_LIMIT {
+        eprintln!("  ... and {} more", report.unmapped_gear.len() - REPORT_LIMIT);
+    }
+}
+
+fn print_unknown_skills(report: &IngestReport) {
+    if report.unknown_skills.is_empty() {
+        return;
+    }
+
+    eprintln!("{} slotted skills have no metadata", report.unknown_skills.len());
+    for skill in report.unknown_skills.iter().take(REPORT_LIMIT) {
+        eprintln!("  {} (slotted {} times)", skill.name, skill.count);
+    }
+    if report.unknown_skills.len() > REPORT_LIMIT {
+        eprintln!("  ... and {} more", report.unknown_skills.len() - REPORT_LIMIT);
+    }
+}
+
+fn write_gear_patch(args: &Args, report: &IngestReport) -> Result<(), String> {
+    let Some(path) = args.path("gear-patch") else {
+        return Ok(());
+    };
+    fs::write(&path, data::set_ids_patch(&report.unmapped_gear))
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn print_set_issues(report: &IngestReport) {
+    if report.set_issues.is_empty() {
+        return;
+    }
+
+    eprintln!("{} problems between the set data and set ids files", report.set_issues.len());
+    for issue in report.set_issues.iter().take(REPORT_LIMIT) {
+        eprintln!("  {}", issue);
+    }
+    if report.set_issues.len() > REPORT_LIMIT {
+        eprintln!("  ... and {} more", report.set_issues.len() - REPORT_LIMIT);
+    }
+}
+
+fn print_report(report: &IngestReport, master: &MasterTable) {
+    print_set_issues(report);
+    print_unmapped_gear(report, master);
+    print_unknown_skills(report);
+
+    if report.rows_without_icons > 0 {
+        eprintln!(
+            "{} rows have gear without icons, their weapons and armour weights will show as unknown",
+            report.rows_without_icons
+        );
+    }
+
+    if report.names_matched + report.names_ignored > 0 {
+        println!(
+            "Custom names: {} matched players in the table, {} ignored",
+            report.names_matched, report.names_ignored
+        );
+    }
+
+    if report.errors.is_empty() {
+        return;
+    }
+
+    let mut per_file: BTreeMap<String, usize> = BTreeMap::new();
+    for error in &report.errors {
+        *per_file.entry(error.file().display().to_string()).or_insert(0) += 1;
+    }
+
+    eprintln!(
+        "{} errors, {} of {} rows skipped",
+        report.errors.len(), report.rows_skipped, report.rows_read
+    );
+    for (file, count) in &per_file {
+        eprintln!("  {}: {}", file, count);
+    }
+    for error in report.errors.iter().take(REPORT_LIMIT) {
+        eprintln!("  {}", error);
+    }
+    if report.errors.len() > REPORT_LIMIT {
+        eprintln!("  ... and {} more", report.errors.len() - REPORT_LIMIT);
+    }
+}
+
+fn write_outputs(args: &Args, master: &MasterTable, default_out: PathBuf) -> Result<(), String> {
+    let out = args.path("out").unwrap_or(default_out);
+    let json = if args.has("no-json") {
+        None
+    } else {
+        Some(args.path("json").unwrap_or_else(|| PathBuf::from("data/master_table.json")))
+    };
+
+    ingest::write_master_table(master, &out, json.as_deref()).map_err(|e| e.to_string())?;
+
+    match &json {
+        Some(json) => println!("Wrote {} and {}", out.display(), json.display()),
+        None => println!("Wrote {}", out.display()),
+    }
+    print_summary(master);
+    Ok(())
+}
+
+fn build(args: &Args) -> Result<(), String> {
+    let (master, report) = ingest::build_master_table(&args.ingest_config()).map_err(|e| e.to_string())?;
+    print_report(&report, &master);
+    write_gear_patch(args, &report)?;
+    write_outputs(args, &master, PathBuf::from("data/master_table.bin"))
+}
+
+fn merge(args: &Args) -> Result<(), String> {
+    let existing = args.table()?;
+    let (rows, players, skills) = (existing.rows.len(), existing.players.len(), existing.skills.len());
+
+    let (master, report) = ingest::merge_into_master_table(existing, &args.ingest_config()).map_err(|e| e.to_string())?;
+    print_report(&report, &master);
+    write_gear_patch(args, &report)?;
+
+    println!(
+        "Added {} rows, {} players, {} skills",
+        master.rows.len() - rows,
+        master.players.len() - players,
+        master.skills.len() - skills
+    );
+    write_outputs(args, &master, args.table_path())
+}
+
+fn validate(args: &Args) -> Result<(), String> {
+    let config = IngestConfig { mode: IngestMode::Lenient, ..args.ingest_config() };
+    let (master, report) = ingest::build_master_table(&config).map_err(|e| e.to_string())?;
+    print_report(&report, &master);
+    write_gear_patch(args, &report)?;
+    print_summary(&master);
+
+    let problems = report.errors.len() + report.set_issues.len();
+    if problems == 0 {
+        println!("Inputs are valid");
+        Ok(())
+    } else {
+        Err(format!("found {} problems in the inputs", problems))
+    }
+}
+
+fn inspect(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(20);
+    let partition: Option<u8> = args.number("partition")?;
+
+    let player_id = match args.options.get("player") {
+        Some(name) => {
+            let name = name.strip_prefix('@').unwrap_or(name);
+            let player = master
+                .players
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("no player named @{} in the table", name))?;
+            Some(player.id)
+        }
+        None => None,
+    };
+
+    let skill_names: HashMap<u16, &str> = master
+        .skills
+        .iter()
+        .map(|s| (s.id, s.display_name.as_deref().unwrap_or(&s.name)))
+        .collect();
+    let set_names: HashMap<u16, &str> = master.sets.iter().map(|s| (s.id, s.name.as_str())).collect();
+
+    print_summary(&master);
+
+    let rows = master
+        .rows
+        .iter()
+        .filter(|r| player_id.is_none_or(|id| r.player_id == id))
+        .filter(|r| partition.is_none_or(|p| r.partition_id == p))
+        .take(limit);
+
+    for row in rows {
+        let player = master.player(row.player_id).map_or("?", |p| p.name.as_str());
+        let bar = |bar: u8| -> Vec<&str> {
+            row.skills
+                .iter()
+                .filter(|s| s.bar() == bar)
+                .map(|s| skill_names.get(&s.skill_id).copied().unwrap_or("?"))
+                .collect()
+        };
+        // a set's pieces needn't be in neighbouring slots
+        let sets: BTreeSet<&str> = row
+            .set_ids()
+            .map(|id| set_names.get(&id).copied().unwrap_or("Unknown"))
+            .collect();
+
+        println!();
+        println!(
+            "{}. @{} {:.1}k {} - {} - {}",
+            row.ranking,
+            player,
+            row.dps as f32 / 1000.0,
+            if row.boss { "ST" } else { "AOE" },
+            boss_to_boss_name(row.boss_id),
+            partition_to_name(row.partition_id)
+        );
+        println!("   bar 1:  {}", bar(1).join(", "));
+        println!("   bar 2:  {}", bar(2).join(", "));
+        println!("   sets:   {}", sets.into_iter().collect::<Vec<_>>().join(", "));
+    }
+
+    Ok(())
+}
+
+fn stats(args: &Args) -> Result<(), String> {
+    let path = args.table_path();
+    let bytes = fs::read(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let (version, _) = format::read_header(&bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let master = data::decode_master_table(&bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    println!("File version: {} (current {})", version, format::CURRENT_VERSION);
+    print_summary(&master);
+
+    let mut per_partition: BTreeMap<u8, (u32, u32)> = BTreeMap::new();
+    let mut per_boss: BTreeMap<u8, u32> = BTreeMap::new();
+
+    for row in &master.rows {
+        let entry = per_partition.entry(row.partition_id).or_insert((0, 0));
+        if row.boss {
+            entry.1 += 1;
+        } else {
+            entry.0 += 1;
+        }
+        *per_boss.entry(row.boss_id).or_insert(0) += 1;
+    }
+
+    println!();
+    println!("{:<40} {:>8} {:>8}", "Partition", "AOE", "ST");
+    for (partition_id, (aoe, st)) in &per_partition {
+        println!("{:<40} {:>8} {:>8}", partition_to_name(*partition_id), aoe, st);
+    }
+
+    println!();
+    println!("{:<40} {:>8}", "Boss", "Rows");
+    for (boss_id, count) in &per_boss {
+        println!("{:<40} {:>8}", format!("{} ({})", boss_to_boss_name(*boss_id), boss_id), count);
+    }
+
+    Ok(())
+}
+
+fn slots(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(5);
+    let filter = args.row_filter()?;
+
+    print_summary(&master);
+
+    let mut slots: Vec<u8> = master.rows.iter().flat_map(|r| r.gear.iter().map(|g| g.slot)).collect();
+    slots.sort();
+    slots.dedup();
+
+    for slot in slots {
+        println!();
+        println!("{}", data::gear_slot_name(slot));
+        for (set, count) in data::top_n_sets_for_slot(&master, &filter, slot, limit) {
+            println!("  {:<40} {:>8}", set.name, count);
+        }
+    }
+
+    Ok(())
+}
+
+fn bars(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(20);
+    let filter = args.row_filter()?;
+
+    let skill_name = |s: &data::Skill| s.display_name.clone().unwrap_or_else(|| s.name.clone());
+
+    print_summary(&master);
+
+    println!();
+    println!("{:<40} {:>8} {:>8} {:>8}", "Skill", "Slotted", "Front", "Back");
+    for (skill, usage) in data::skill_bar_usage(&master, &filter).into_iter().take(limit) {
+        println!(
+            "{:<40} {:>8} {:>7.1}% {:>7.1}%",
+            skill_name(&skill),
+            usage.total(),
+            usage.front_percent(),
+            100.0 - usage.front_percent()
+        );
+    }
+
+    println!();
+    println!("{:<70} {:>8}", "Ultimates", "Rows");
+    for ((first, second), pct) in data::top_n_ultimate_pairs(&master, &filter, limit) {
+        println!("{:<70} {:>7.1}%", format!("{} + {}", skill_name(&first), skill_name(&second)), pct);
+    }
+
+    Ok(())
+}
+
+fn classes(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(20);
+    let filter = args.row_filter()?;
+
+    print_summary(&master);
+
+    println!();
+    println!("{:<40} {:>8}", "Main class", "Rows");
+    for (class, pct) in data::percent_players_with_classes(&master, &filter) {
+        println!("{:<40} {:>7.1}%", class.name(), pct);
+    }
+
+    println!();
+    println!("{:<40} {:>8}", "Class combination", "Rows");
+    for (classes, pct) in data::percent_players_with_class_combos(&master, &filter).into_iter().take(limit) {
+        println!("{:<40} {:>7.1}%", data::class_combo_label(&classes), pct);
+    }
+
+    println!();
+    println!("{:<40} {:>8}", "Partition", "Subclassed");
+    for (partition, pct) in data::subclassed_percent_per_partition(&master, &filter) {
+        println!("{:<40} {:>7.1}%", partition_to_name(partition), pct);
+    }
+
+    Ok(())
+}
+
+fn builds(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(20);
+    let filter = args.row_filter()?;
+    let options = BuildOptions {
+        skill_similarity: args.number("similarity")?,
+        min_rows: args.number("min-rows")?.unwrap_or(0),
+    };
+
+    let skill_name = |s: &data::Skill| s.display_name.clone().unwrap_or_else(|| s.name.clone());
+
+    print_summary(&master);
+
+    for archetype in builds::archetypes(&master, &filter, &options).into_iter().take(limit) {
+        println!();
+        println!(
+            "{}: {} rows, {:.1}%, {:.1}k avg dps, {:.1} avg rank",
+            archetype.label(),
+            archetype.rows,
+            archetype.share,
+            archetype.average_dps / 1000.0,
+            archetype.average_rank
+        );
+        println!("   skills: {}", archetype.core_skills.iter().map(skill_name).collect::<Vec<_>>().join(", "));
+        for stats in &archetype.partitions {
+            println!(
+                "   {:<40} {:>6} {:>7.1}% {:>8.1}k {:>6.1}",
+                partition_to_name(stats.partition_id),
+                stats.rows,
+                stats.share,
+                stats.average_dps / 1000.0,
+                stats.average_rank
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn companions(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(20);
+    let min_rows: u32 = args.number("min-rows")?.unwrap_or(10);
+    let filter = args.row_filter()?;
+
+    let skill_name = |s: &data::Skill| s.display_name.clone().unwrap_or_else(|| s.name.clone());
+
+    let wanted = args.options.get("skill").ok_or("missing --skill")?;
+    let skill = master
+        .skills
+        .iter()
+        .find(|s| s.id.to_string() == *wanted || skill_name(s).eq_ignore_ascii_case(wanted) || s.name.eq_ignore_ascii_case(wanted))
+        .ok_or_else(|| format!("no skill '{}' in the table", wanted))?;
+
+    print_summary(&master);
+
+    println!();
+    println!("Usually slotted with {}", skill_name(skill));
+    println!("{:<40} {:>8} {:>8} {:>9} {:>8}", "Skill", "Rows", "With", "Baseline", "Lift");
+    for companion in data::skill_companions(&master, &filter, skill.id, min_rows).into_iter().take(limit) {
+        println!(
+            "{:<40} {:>8} {:>7.1}% {:>8.1}% {:>8.2}",
+            skill_name(&companion.skill),
+            companion.count,
+            companion.with_percent,
+            companion.baseline_percent,
+            companion.lift
+        );
+    }
+
+    Ok(())
+}
+
+fn pairs(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    let limit: usize = args.number("limit")?.unwrap_or(20);
+    let filter = args.row_filter()?;
+
+    print_summary(&master);
+
+    println!();
+    println!("{:<70} {:>8} {:>8} {:>8}", "Set pair", "Rows", "P(B|A)", "Lift");
+    for pair in data::set_co_occurrence(&master, &filter).into_iter().take(limit) {
+        println!(
+            "{:<70} {:>8} {:>7.1}% {:>8.2}",
+            format!("{} -> {}", pair.first.name, pair.second.name),
+            pair.count,
+            pair.second_given_first,
+            pair.lift
+        );
+    }
+
+    println!();
+    println!("{:<100} {:>8}", "Full set combination", "Rows");
+    for (combo, pct) in data::top_n_set_combos(&master, &filter, limit) {
+        println!("{:<100} {:>7.1}%", data::set_combo_label(&combo), pct);
+    }
+
+    Ok(())
+}
+
+fn query(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+
+    let group_by = match args.options.get("group").map(String::as_str).unwrap_or("skill") {
+        "skill" => GroupBy::Skill,
+        "skill-slot" => GroupBy::SkillSlot,
+        "set" => GroupBy::Set,
+        "class" => GroupBy::Class,
+        "boss" => GroupBy::Boss,
+        "partition" => GroupBy::Partition,
+        "player" => GroupBy::Player,
+        other => return Err(format!("invalid value '{}' for --group", other)),
+    };
+    let measure = match args.options.get("measure").map(String::as_str).unwrap_or("share") {
+        "count" => Measure::Count,
+        "share" => Measure::Share,
+        "normalised" => Measure::NormalisedCount,
+        "dps" => Measure::AverageDps,
+        "rank" => Measure::AverageRank,
+        other => return Err(format!("invalid value '{}' for --measure", other)),
+    };
+
+    let query = Query {
+        limit: Some(args.number("limit")?.unwrap_or(20)),
+        min_rows: args.number("min-rows")?.unwrap_or(0),
+        ..Query::new(group_by, measure, &args.row_filter()?)
+    };
+
+    print_summary(&master);
+
+    println!();
+    println!("{:<40} {:>8} {:>12}", "Group", "Rows", "Value");
+    for row in query.run(&master) {
+        println!("{:<40} {:>8} {:>12.1}", row.key.label(&master), row.rows, row.value);
+    }
+
+    Ok(())
+}
+
+fn migrate(args: &Args) -> Result<(), String> {
+    let master = args.table()?;
+    write_outputs(args, &master, args.table_path())
+}
+
+fn run(raw: &[String]) -> Result<(), String> {
+    let Some((command, rest)) = raw.split_first() else {
+        return Err(format!("missing command\n\n{}", USAGE));
+    };
+
+    if command == "help" || command == "--help" || command == "-h" {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+
+    let args = Args::parse(rest)?;
+
+    match command.as_str() {
+        "build" => build(&args),
+        "merge" => merge(&args),
+        "validate" => validate(&args),
+        "inspect" => inspect(&args),
+        "stats" => stats(&args),
+        "slots" => slots(&args),
+        "bars" => bars(&args),
+        "classes" => classes(&args),
+        "builds" => builds(&args),
+        "companions" => companions(&args),
+        "pairs" => pairs(&args),
+        "query" => query(&args),
+        "migrate" => migrate(&args),
+        other => Err(format!("unknown command '{}'\n\n{}", other, USAGE)),
+    }
+}
+
+fn main() -> ExitCode {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+
+    match run(&raw) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::{cmp::Reverse, collections::{BTreeMap, HashMap, HashSet}};
+
+use crate::{data::{Class, MasterTable, SetCombo, Skill, TableRow, canonical_row_sets, canonical_set_ids, set_combo_label, set_or_unknown, skill_or_unknown}, filter::RowFilter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BuildOptions {
+    /// split each class and set combination further into groups whose skill
+    /// bars have at least this Jaccard similarity, 0 to 1. Each row is only
+    /// compared with the first row of each group, in table order, so the
+    /// groups can change when the same rows come in another order
+    pub skill_similarity: Option<f64>,
+    /// leave out archetypes with fewer rows
+    pub min_rows: u32,
+}
+
+/// How an archetype did in one partition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchetypeStats {
+    pub partition_id: u8,
+    pub rows: u32,
+    /// percent of the partition's rows with any known set
+    pub share: f64,
+    pub average_dps: f64,
+    pub average_rank: f64,
+}
+
+/// Rows with the same main class and set combination, and with similar skill
+/// bars when `BuildOptions::skill_similarity` is set. Perfected sets count as
+/// their base set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Archetype {
+    pub class: Option<Class>,
+    pub sets: SetCombo,
+    /// skills slotted on at least half the rows, most slotted first
+    pub core_skills: Vec<Skill>,
+    pub rows: u32,
+    /// percent of the rows in the filter with any known set
+    pub share: f64,
+    pub average_dps: f64,
+    pub average_rank: f64,
+    /// in partition order
+    pub partitions: Vec<ArchetypeStats>,
+}
+
+impl Archetype {
+    pub fn label(&self) -> String {
+        let class = self.class.map(|c| c.name()).unwrap_or("Unknown");
+        format!("{} - {}", class, set_combo_label(&self.sets))
+    }
+}
+
+/// Archetypes of the rows in the filter, most common first. Rows wearing no
+/// known set are left out.
+pub fn archetypes(master: &MasterTable, filter: &RowFilter, options: &BuildOptions) -> Vec<Archetype> {
+    let canonical_id = canonical_set_ids(master);
+
+    let mut rows_per_partition: HashMap<u8, u32> = HashMap::new();
+    let mut groups: BTreeMap<(Option<Class>, Vec<u16>), Vec<&TableRow>> = BTreeMap::new();
+    for row in filter.rows(master) {
+        let sets = canonical_row_sets(row, &canonical_id);
+        if sets.is_empty() {
+            continue;
+        }
+        *rows_per_partition.entry(row.partition_id).or_insert(0) += 1;
+        groups.entry((row.main_class(), sets)).or_default().push(row);
+    }
+    let total: u32 = rows_per_partition.values().sum();
+
+    let mut results: Vec<Archetype> = groups
+        .into_iter()
+        .flat_map(|((class, sets), rows)| {
+            let clusters = match options.skill_similarity {
+                Some(threshold) => cluster_by_skills(rows, threshold),
+                None => vec![rows],
+            };
+            clusters.into_iter().map(move |rows| (class, sets.clone(), rows))
+        })
+        .filter(|(_, _, rows)| rows.len() as u32 >= options.min_rows.max(1))
+        .map(|(class, sets, rows)| {
+            let mut per_partition: BTreeMap<u8, Vec<&TableRow>> = BTreeMap::new();
+            for row in &rows {
+                per_partition.entry(row.partition_id).or_default().push(row);
+            }
+            let partitions = per_partition
+                .into_iter()
+                .map(|(partition_id, rows)| {
+                    let (average_dps, average_rank) = averages(&rows);
+                    ArchetypeStats {
+                        partition_id,
+                        rows: rows.len() as u32,
+                        share: rows.len() as f64 * 100.0 / rows_per_partition[&partition_id] as f64,
+                        average_dps,
+                        average_rank,
+                    }
+                })
+                .collect();
+
+            let (average_dps, average_rank) = averages(&rows);
+            Archetype {
+                class,
+                sets: sets.into_iter().map(|id| set_or_unknown(master, id)).collect(),
+                core_skills: core_skills(&rows).into_iter().map(|id| skill_or_unknown(master, id)).collect(),
+                rows: rows.len() as u32,
+                share: rows.len() as f64 * 100.0 / total as f64,
+                average_dps,
+                average_rank,
+                partitions,
+            }
+        })
+        .collect();
+
+    // stable, so ties keep class and set order
+    results.sort_by_key(|a| Reverse(a.rows));
+    results
+}
+
+/// Average dps and rank of the rows.
+fn averages(rows: &[&TableRow]) -> (f64, f64) {
+    let n = rows.len() as f64;
+    let dps: u64 = rows.iter().map(|r| r.dps as u64).sum();
+    let rank: u64 = rows.iter().map(|r| r.ranking as u64).sum();
+    (dps as f64 / n, rank as f64 / n)
+}
+
+/// Skills slotted on at least half the rows, most slotted first.
+fn core_skills(rows: &[&TableRow]) -> Vec<u16> {
+    let mut freq: HashMap<u16, usize> = HashMap::new();
+    for row in rows {
+        for id in row.skill_ids().collect::<HashSet<u16>>() {
+            *freq.entry(id).or_insert(0) += 1;
+        }
+    }
+
+    let mut skills: Vec<(u16, usize)> = freq.into_iter().filter(|(_, count)| count * 2 >= rows.len()).collect();
+    skills.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    skills.into_iter().map(|(id, _)| id).collect()
+}
+
+/// Greedy clustering in row order: each row joins the first cluster whose
+/// first row's skills are at least `threshold` similar, or starts a new one.
+fn cluster_by_skills(rows: Vec<&TableRow>, threshold: f64) -> Vec<Vec<&TableRow>> {
+    let mut clusters: Vec<(HashSet<u16>, Vec<&TableRow>)> = Vec::new();
+    for row in rows {
+        let skills: HashSet<u16> = row.skill_ids().collect();
+        match clusters.iter_mut().find(|(leader, _)| jaccard(leader, &skills) >= threshold) {
+            Some((_, members)) => members.push(row),
+            None => clusters.push((skills, vec![row])),
+        }
+    }
+    clusters.into_iter().map(|(_, members)| members).collect()
+}
+
+/// Skills in both over skills in either, 1 when both are empty.
+fn jaccard(a: &HashSet<u16>, b: &HashSet<u16>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::tests::{close, row, table};
+
+    fn skill_sets(clusters: &[Vec<&TableRow>]) -> Vec<Vec<Vec<u16>>> {
+        clusters.iter().map(|rows| rows.iter().map(|r| r.skill_ids().collect()).collect()).collect()
+    }
+
+    /// Skill 1 makes a row Arcanist and skill 4 Nightblade, skills past 4
+    /// have no class.
+    fn master() -> MasterTable {
+        table(vec![
+            row(27, 1, 100, &[1, 5, 6], &[1, 3]),
+            // Perfected Slivers counts as Slivers
+            row(27, 2, 200, &[1, 5, 7], &[2, 3]),
+            row(28, 1, 300, &[1, 5, 8], &[1, 3, 3]),
+            row(27, 3, 400, &[1, 5], &[3]),
+            row(28, 2, 500, &[4], &[1, 3]),
+            // no known set, left out of every share
+            row(28, 3, 1, &[1], &[0]),
+        ])
+    }
+
+    #[test]
+    fn groups_by_class_and_base_sets() {
+        let master = master();
+        let results = archetypes(&master, &RowFilter::default(), &BuildOptions::default());
+
+        let summary: Vec<_> = results
+            .iter()
+            .map(|a| {
+                let sets: Vec<u16> = a.sets.iter().map(|s| s.id).collect();
+                let core: Vec<u16> = a.core_skills.iter().map(|s| s.id).collect();
+                (a.class, sets, a.rows, core)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some(Class::Arcanist), vec![1, 3], 3, vec![1, 5]),
+                (Some(Class::Arcanist), vec![3], 1, vec![1, 5]),
+                (Some(Class::Nightblade), vec![1, 3], 1, vec![4]),
+            ]
+        );
+        assert_eq!(results[0].label(), "Arcanist - Slivers + Relequen");
+    }
+
+    #[test]
+    fn shares_are_out_of_rows_with_known_sets() {
+        let master = master();
+        let results = archetypes(&master, &RowFilter::default(), &BuildOptions::default());
+
+        // 5 rows with a known set, 3 in partition 27 and 2 in 28
+        let first = &results[0];
+        assert!(close(first.share, 60.0));
+        assert!(close(first.average_dps, 200.0));
+        assert!(close(first.average_rank, 4.0 / 3.0));
+
+        let partitions: Vec<(u8, u32)> = first.partitions.iter().map(|p| (p.partition_id, p.rows)).collect();
+        assert_eq!(partitions, vec![(27, 2), (28, 1)]);
+        assert!(close(first.partitions[0].share, 200.0 / 3.0));
+        assert!(close(first.partitions[0].average_dps, 150.0));
+        assert!(close(first.partitions[0].average_rank, 1.5));
+        assert!(close(first.partitions[1].share, 50.0));
+
+        let shares: f64 = results.iter().map(|a| a.share).sum();
+        assert!(close(shares, 100.0));
+    }
+
+    #[test]
+    fn min_rows_leaves_out_small_archetypes() {
+        let master = master();
+        let options = BuildOptions { min_rows: 2, ..Default::default() };
+        let results = archetypes(&master, &RowFilter::default(), &options);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].rows, 3);
+        // shares are still out of every row, not just the kept ones
+        assert!(close(results[0].share, 60.0));
+    }
+
+    #[test]
+    fn clusters_rows_with_similar_skills() {
+        let rows = vec![
+            row(27, 1, 100, &[1, 5, 6, 7], &[1]),
+            // 3 shared out of 5, 0.6 like the first row
+            row(27, 2, 100, &[1, 5, 6, 8], &[1]),
+            // 1 shared out of 7 with the first row
+            row(27, 3, 100, &[1, 9, 10, 11], &[1]),
+            row(27, 4, 100, &[1, 9, 10, 12], &[1]),
+        ];
+        let refs: Vec<&TableRow> = rows.iter().collect();
+
+        assert_eq!(
+            skill_sets(&cluster_by_skills(refs.clone(), 0.6)),
+            vec![vec![vec![1, 5, 6, 7], vec![1, 5, 6, 8]], vec![vec![1, 9, 10, 11], vec![1, 9, 10, 12]]]
+        );
+        assert_eq!(cluster_by_skills(refs.clone(), 0.61).len(), 4);
+        assert_eq!(cluster_by_skills(refs, 0.0).len(), 1);
+
+        let master = table(rows);
+        let options = BuildOptions { skill_similarity: Some(0.6), ..Default::default() };
+        let results = archetypes(&master, &RowFilter::default(), &options);
+        let core: Vec<Vec<u16>> = results.iter().map(|a| a.core_skills.iter().map(|s| s.id).collect()).collect();
+        assert_eq!(core, vec![vec![1, 5, 6, 7, 8], vec![1, 9, 10, 11, 12]]);
+    }
+
+    #[test]
+    fn clusters_compare_with_the_first_row_only() {
+        // the middle row is 0.6 like both others, which are 0.2 like each other
+        let rows = [row(27, 1, 100, &[1, 2, 3], &[1]), row(27, 2, 100, &[1, 2, 3, 4, 5], &[1]), row(27, 3, 100, &[3, 4, 5], &[1])];
+
+        let in_order: Vec<&TableRow> = rows.iter().collect();
+        assert_eq!(cluster_by_skills(in_order, 0.5).len(), 2);
+
+        let middle_first = vec![&rows[1], &rows[0], &rows[2]];
+        assert_eq!(cluster_by_skills(middle_first, 0.5).len(), 1);
+    }
+
+    #[test]
+    fn jaccard_is_shared_over_either() {
+        let set = |ids: &[u16]| ids.iter().copied().collect::<HashSet<u16>>();
+
+        assert!(close(jaccard(&set(&[1, 2]), &set(&[2, 3])), 1.0 / 3.0));
+        assert!(close(jaccard(&set(&[1, 2]), &set(&[1, 2])), 1.0));
+        assert!(close(jaccard(&set(&[1]), &set(&[2])), 0.0));
+        assert!(close(jaccard(&set(&[]), &set(&[])), 1.0));
+    }
+
+    #[test]
+    fn core_skills_are_on_at_least_half_the_rows() {
+        let even = [row(27, 1, 100, &[1, 5, 1], &[]), row(27, 2, 100, &[1, 6], &[])];
+        assert_eq!(core_skills(&even.iter().collect::<Vec<_>>()), vec![1, 5, 6]);
+
+        let odd = [row(27, 1, 100, &[1, 5], &[]), row(27, 2, 100, &[1, 5, 6], &[]), row(27, 3, 100, &[1, 7], &[])];
+        assert_eq!(core_skills(&odd.iter().collect::<Vec<_>>()), vec![1, 5]);
+    }
+}
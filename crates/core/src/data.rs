@@ -0,0 +1,2994 @@
+use std::{cmp::Ordering, collections::{BTreeMap, HashMap, HashSet}, fmt, path::{Path, PathBuf}};
+use bitcode::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{dates::Date, filter::RowFilter, format::{self, FormatError}, index::TableIndex, query::{GroupBy, GroupKey, Measure, Query}};
+
+#[derive(Debug, Serialize, Deserialize, Encode, Decode)]
+pub struct Row {
+    pub zone: u32,
+    pub boss: u32,
+    pub partition: u32,
+    pub row_id: String,
+    pub name: String,
+    pub at_name: String,
+    pub dps: f32,
+    /// as scraped from esologs, milliseconds since the unix epoch
+    pub timestamp: Option<u64>,
+    pub talents: Vec<String>,
+    pub gear: Vec<GearItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Encode, Decode)]
+pub struct GearItem {
+    pub name: String,
+    pub icon: String,
+    pub id: u32,
+    /// position in the scraped gear list, see `gear_slot_name`
+    pub slot: u8,
+}
+
+/// What was wrong with a single line of an input file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldError {
+    MissingFields { expected: usize, found: usize },
+    Missing { field: &'static str },
+    Invalid { field: &'static str, value: String },
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::MissingFields { expected, found } => write!(f, "expected {} fields, found {}", expected, found),
+            FieldError::Missing { field } => write!(f, "missing {}", field),
+            FieldError::Invalid { field, value } => write!(f, "invalid {} '{}'", field, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IngestError {
+    Io { file: PathBuf, message: String },
+    Json { file: PathBuf, message: String },
+    /// `line` is 1-based and counts the header line
+    Parse { file: PathBuf, line: usize, error: FieldError },
+    /// a file or folder name that should carry a zone, boss or partition number
+    Path { file: PathBuf, error: FieldError },
+    /// a row from `file` for a ranking slot the table already has
+    Duplicate { file: PathBuf, key: RowKey },
+}
+
+impl IngestError {
+    pub fn file(&self) -> &Path {
+        match self {
+            IngestError::Io { file, .. }
+            | IngestError::Json { file, .. }
+            | IngestError::Parse { file, .. }
+            | IngestError::Path { file, .. }
+            | IngestError::Duplicate { file, .. } => file,
+        }
+    }
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::Io { file, message } => write!(f, "{}: {}", file.display(), message),
+            IngestError::Json { file, message } => write!(f, "{}: {}", file.display(), message),
+            IngestError::Parse { file, line, error } => write!(f, "{}:{}: {}", file.display(), line, error),
+            IngestError::Path { file, error } => write!(f, "{}: {}", file.display(), error),
+            IngestError::Duplicate { file, key } => write!(
+                f,
+                "{}: rank {} {} on {} in {} is already in the table",
+                file.display(),
+                key.ranking,
+                if key.boss { "ST" } else { "AOE" },
+                boss_to_boss_name(key.boss_id),
+                partition_to_name(key.partition_id)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IngestMode {
+    /// stop at the first bad line
+    #[default]
+    Strict,
+    /// skip bad lines and keep going, collecting every error
+    Lenient,
+}
+
+/// Errors collected while ingesting. In strict mode this never holds more
+/// than the error that stopped the build.
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    pub mode: IngestMode,
+    pub errors: Vec<IngestError>,
+    pub rows_read: usize,
+    pub rows_skipped: usize,
+    /// LibCustomNames entries for players in the table
+    pub names_matched: usize,
+    /// LibCustomNames entries for players that never ranked
+    pub names_ignored: usize,
+    /// gear ids missing from `set_ids.csv`, most worn first
+    pub unmapped_gear: Vec<UnmappedGear>,
+    /// disagreements between `set_data.csv` and `set_ids.csv`
+    pub set_issues: Vec<SetTableIssue>,
+    /// slotted skills missing from the skill metadata, most slotted first
+    pub unknown_skills: Vec<UnknownSkill>,
+    /// rows whose gear came without icons, so their weapons and armour
+    /// weights can't be classified
+    pub rows_without_icons: usize,
+}
+
+/// A gear item id that isn't in `set_ids.csv`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmappedGear {
+    pub item_id: u32,
+    /// `GearItem.name` the first time the id was seen
+    pub name: String,
+    /// how many times it was worn in the ingested rows
+    pub count: usize,
+    /// set matched by `set_for_item_name`, only a suggestion for
+    /// `set_ids.csv`; the rows keep 0 until the id is added there
+    pub suggested_set: Option<u16>,
+}
+
+impl IngestReport {
+    pub fn new(mode: IngestMode) -> Self {
+        IngestReport { mode, ..Default::default() }
+    }
+
+    /// Hands the error back in strict mode so the caller can bail with `?`,
+    /// otherwise keeps it for the summary.
+    pub(crate) fn record(&mut self, error: IngestError) -> Result<(), IngestError> {
+        match self.mode {
+            IngestMode::Strict => Err(error),
+            IngestMode::Lenient => {
+                self.errors.push(error);
+                Ok(())
+            }
+        }
+    }
+
+    fn skip_row(&mut self, error: IngestError) -> Result<(), IngestError> {
+        self.record(error)?;
+        self.rows_skipped += 1;
+        Ok(())
+    }
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                if in_quotes && chars.peek() == Some(&'"') {
+                    current.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = !in_quotes;
+                }
+            }
+            ',' if !in_quotes => {
+                fields.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+
+    // always keep the last field, an empty gear or talent column is still a column
+    fields.push(current.trim().to_string());
+
+    fields
+}
+
+/// Empty slots stay in as empty strings so the skills after them keep their
+/// bar position.
+fn parse_talents(s: &str) -> Vec<String> {
+    let trimmed = s.trim().trim_start_matches('[').trim_end_matches(']');
+    if trimmed.trim().is_empty() {
+        return Vec::new();
+    }
+    trimmed
+        .split(',')
+        .map(|x| x.trim().trim_matches('\'').to_string())
+        .collect()
+}
+
+/// The partition files written by the scraper join talents with `;` instead of
+/// writing a python list.
+fn parse_talent_field(s: &str) -> Vec<String> {
+    if s.trim_start().starts_with('[') {
+        return parse_talents(s);
+    }
+    if s.trim().is_empty() {
+        return Vec::new();
+    }
+    s.split(';').map(|x| x.trim().to_string()).collect()
+}
+
+/// Gear in the partition files is `id:icon:name` joined with `;`, with the
+/// icon's file name only, so the name is the one part that may hold a `:`.
+/// Older scrapes wrote `id:name` without icons, which leaves `icon` empty;
+/// those rows are counted in `IngestReport::rows_without_icons`.
+fn parse_gear_field(s: &str) -> Result<Vec<GearItem>, FieldError> {
+    if s.trim_start().starts_with('[') {
+        return Ok(parse_gear(s));
+    }
+    let mut gear = Vec::new();
+    // empty slots are skipped but keep their position
+    for (slot, part) in s.split(';').map(str::trim).enumerate().filter(|(_, p)| !p.is_empty()) {
+        let fields: Vec<&str> = part.splitn(3, ':').collect();
+        let id: u32 = fields[0]
+            .trim()
+            .parse()
+            .map_err(|_| FieldError::Invalid { field: "gear", value: part.to_string() })?;
+        let (icon, name) = match fields[1..] {
+            [icon, name] => (icon, name),
+            [name] => ("", name),
+            _ => ("", ""),
+        };
+        gear.push(GearItem { name: name.trim().to_string(), icon: icon.trim().to_string(), id, slot: slot as u8 });
+    }
+    Ok(gear)
+}
+
+fn parse_gear(s: &str) -> Vec<GearItem> {
+    let mut gear = Vec::new();
+    let current = s.trim().trim_start_matches('[').trim_end_matches(']').to_string();
+
+    let parts: Vec<&str> = current.split("},").collect();
+    // empty slots are skipped but keep their position
+    for (slot, part) in parts.into_iter().enumerate() {
+        let part = part.trim().trim_start_matches('{').trim_end_matches('}');
+
+        let mut name = String::new();
+        let mut icon = String::new();
+        let mut id = 0;
+
+        for kv in part.split(',') {
+            let kv = kv.trim();
+            let mut iter = kv.splitn(2, ':');
+            let key = iter.next().unwrap_or("").trim().trim_matches('\'').trim_matches('"');
+            let value = iter.next().unwrap_or("").trim().trim_matches('\'').trim_matches('"');
+
+            match key {
+                "name" => name = value.to_string(),
+                "icon" => icon = value.to_string(),
+                "id" => id = value.parse::<u32>().unwrap_or(0),
+                _ => {}
+            }
+        }
+
+        if !name.is_empty() && !icon.is_empty() {
+            gear.push(GearItem { name, icon, id, slot: slot as u8 });
+        }
+    }
+
+    gear
+}
+
+fn parse_field<T: std::str::FromStr>(fields: &[String], index: usize, field: &'static str) -> Result<T, FieldError> {
+    fields[index].parse().map_err(|_| FieldError::Invalid { field, value: fields[index].clone() })
+}
+
+/// Ranking is the number after the last `-` of an esologs row id, e.g. `row-12`.
+pub fn ranking_from_row_id(row_id: &str) -> Result<u8, FieldError> {
+    row_id
+        .rsplit_once('-')
+        .and_then(|(_, ranking)| ranking.parse().ok())
+        .ok_or_else(|| FieldError::Invalid { field: "row_id", value: row_id.to_string() })
+}
+
+/// pandas writes the timestamp column as a float once any row is missing one
+fn parse_timestamp(s: &str) -> Result<Option<u64>, FieldError> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse::<u64>()
+        .ok()
+        .or_else(|| s.strip_suffix(".0").and_then(|s| s.parse().ok()))
+        .map(Some)
+        .ok_or_else(|| FieldError::Invalid { field: "timestamp", value: s.to_string() })
+}
+
+fn check_u8_range(value: u32, field: &'static str) -> Result<(), FieldError> {
+    u8::try_from(value).map(|_| ()).map_err(|_| FieldError::Invalid { field, value: value.to_string() })
+}
+
+pub fn parse_csv_line(line: &str) -> Result<Row, FieldError> {
+    let fields = split_csv_line(line);
+    if fields.len() < 10 {
+        return Err(FieldError::MissingFields { expected: 10, found: fields.len() });
+    }
+
+    let zone: u32 = parse_field(&fields, 0, "zone")?;
+    let boss: u32 = parse_field(&fields, 1, "boss")?;
+    let partition: u32 = parse_field(&fields, 2, "partition")?;
+    check_u8_range(zone, "zone")?;
+    check_u8_range(boss, "boss")?;
+    check_u8_range(partition, "partition")?;
+    let row_id = fields[3].clone();
+    ranking_from_row_id(&row_id)?;
+    let name = fields[4].clone();
+    let at_name = fields[5].clone();
+    if at_name.is_empty() {
+        return Err(FieldError::Missing { field: "@name" });
+    }
+    let dps: f32 = parse_field(&fields, 6, "dps")?;
+    let timestamp = parse_timestamp(&fields[7])?;
+
+    let talents = parse_talents(&fields[8]);
+    let gear = parse_gear(&fields[9]);
+
+    Ok(Row {
+        zone,
+        boss,
+        partition,
+        row_id,
+        name,
+        at_name,
+        dps,
+        timestamp,
+        talents,
+        gear,
+    })
+}
+
+pub fn parse_csv_text(text: &str, file: &Path, report: &mut IngestReport) -> Result<Vec<Row>, IngestError> {
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if i == 0 { continue; }
+        if line.trim().is_empty() { continue; }
+        report.rows_read += 1;
+        match parse_csv_line(line) {
+            Ok(row) => rows.push(row),
+            Err(error) => report.skip_row(IngestError::Parse { file: file.to_path_buf(), line: i + 1, error })?,
+        }
+    }
+    Ok(rows)
+}
+
+/// Parses a line of `zone_{zone}/boss_{boss}/partition_{partition}.csv`, which
+/// has no location columns: `row_id,name,@name,dps,timestamp,talents,gear`.
+pub fn parse_partition_csv_line(line: &str, zone: u32, boss: u32, partition: u32) -> Result<Row, FieldError> {
+    let fields = split_csv_line(line);
+    if fields.len() < 7 {
+        return Err(FieldError::MissingFields { expected: 7, found: fields.len() });
+    }
+
+    check_u8_range(zone, "zone")?;
+    check_u8_range(boss, "boss")?;
+    check_u8_range(partition, "partition")?;
+    let row_id = fields[0].clone();
+    ranking_from_row_id(&row_id)?;
+    let name = fields[1].clone();
+    let at_name = fields[2].clone();
+    if at_name.is_empty() {
+        return Err(FieldError::Missing { field: "@name" });
+    }
+    let dps: f32 = parse_field(&fields, 3, "dps")?;
+    let timestamp = parse_timestamp(&fields[4])?;
+
+    let talents = parse_talent_field(&fields[5]);
+    let gear = parse_gear_field(&fields[6])?;
+
+    Ok(Row {
+        zone,
+        boss,
+        partition,
+        row_id,
+        name,
+        at_name,
+        dps,
+        timestamp,
+        talents,
+        gear,
+    })
+}
+
+pub fn parse_partition_csv_text(
+    text: &str,
+    file: &Path,
+    (zone, boss, partition): (u32, u32, u32),
+    report: &mut IngestReport,
+) -> Result<Vec<Row>, IngestError> {
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if i == 0 { continue; }
+        if line.trim().is_empty() { continue; }
+        report.rows_read += 1;
+        match parse_partition_csv_line(line, zone, boss, partition) {
+            Ok(row) => {
+                if row.gear.iter().any(|g| g.icon.is_empty()) {
+                    report.rows_without_icons += 1;
+                }
+                rows.push(row)
+            }
+            Err(error) => report.skip_row(IngestError::Parse { file: file.to_path_buf(), line: i + 1, error })?,
+        }
+    }
+    Ok(rows)
+}
+
+pub fn boss_to_boss_name(boss_id: u8) -> String {
+    let name = match boss_id {
+        4 => "The Mage",
+        8 => "The Warrior",
+        12 => "The Serpent",
+        15 => "Rakkhat",
+        20 => "Assembly General",
+        23 => "Saint Olms the Just",
+        27 => "Z'Maja",
+        43 => "Lokkestiiz",
+        44 => "Yolnahkriin",
+        45 => "Nahviintaas",
+        46 => "Yandir the Butcher",
+        47 => "Captain Vrol",
+        48 => "Lord Falgravn",
+        49 => "Oaxiltso",
+        50 => "Flame-Herald Bahsei",
+        51 => "Xalvakka",
+        52 => "Lylanar and Turlassil",
+        53 => "Reef Guardian",
+        54 => "Tideborn Taleria",
+        55 => "Exarchanic Yaseyla",
+        56 => "Archwizard Twelvane and Chimera",
+        57 => "Ansuul the Tormentor",
+        58 => "Count Ryelaz and Zilyesset",
+        59 => "Orphic Shattered Shard",
+        60 => "Xoryn",
+        61 => "Hall of Fleshcraft",
+        62 => "Jynorah and Skorkhif",
+        63 => "Overfiend Kazpian",
+        _ => "Unknown Boss"
+    };
+    name.to_string()
+}
+
+pub fn zone_to_zone_name(zone_id: u8) -> String {
+    let name = match zone_id {
+        1 => "Aetherian Archive",
+        2 => "Hel Ra Citadel",
+        3 => "Sanctum Ophidia",
+        5 => "Maw of Lorkhaj",
+        6 => "Halls of Fabrication",
+        7 => "Asylum Sanctorium",
+        8 => "Cloudrest",
+        12 => "Sunspire",
+        14 => "Kyne's Aegis",
+        15 => "Rockgrove",
+        16 => "Dreadsail Reef",
+        17 => "Sanity's Edge",
+        18 => "Lucent Citadel",
+        19 => "Ossein Cage",
+        _ => "Unknown Zone"
+    };
+    name.to_string()
+}
+
+/// The esologs zone a boss belongs to, 0 if unknown.
+pub fn boss_to_zone(boss_id: u8) -> u8 {
+    match boss_id {
+        1..=4 => 1,
+        5..=8 => 2,
+        9..=12 => 3,
+        13..=15 => 5,
+        16..=20 => 6,
+        21..=23 => 7,
+        24..=27 => 8,
+        43..=45 => 12,
+        46..=48 => 14,
+        49..=51 => 15,
+        52..=54 => 16,
+        55..=57 => 17,
+        58..=60 => 18,
+        61..=63 => 19,
+        _ => 0,
+    }
+}
+
+pub fn partition_to_name(partition_id: u8) -> String {
+    let partition_name = match partition_id {
+        1 => "Elsweyr (Update 22)",
+        2 => "Scalebreaker (Update 23)",
+        3 => "Dragonhold (Update 24)",
+        4 => "Harrowstorm (Update 25)",
+        5 => "Greymoor (Update 26)",
+        6 => "Stonethorn (Update 27)",
+        7 => "Markarth (Update 28)",
+        8 => "Flames of Ambition (Update 29)",
+        9 => "Blackwood (Update 30)",
+        10 => "Waking Flame (Update 31)",
+        11 => "Deadlands (Update 32)",
+        12 => "Ascending Tide (Update 33)",
+        13 => "High Isle (Update 34)",
+        14 => "Lost Depths (Update 35)",
+        15 => "Firesong (Update 36)",
+        16 => "Scribes of Fate (Update 37)",
+        17 => "Necrom (Update 38)",
+        18 => "Free Update (Update 39)",
+        19 => "Infinite Archive (Update 40)",
+        20 => "Scions of Ithelia (Update 41)",
+        21 => "Gold Road (Update 42)",
+        22 => "Home Tours (Update 43)",
+        23 => "Golden Pursuits (Update 44)",
+        24 => "Fallen Banners (Update 45)",
+        25 => "Western Solstice (Update 46)",
+        26 => "Feast of Shadows (Update 47)",
+        27 => "Eastern Solstice (Update 48)",
+        _ => "Unknown Partition",
+    };
+    partition_name.to_string()
+}
+
+pub fn partition_to_update_id(partition_id: u8) -> String {
+    let partition_name = match partition_id {
+        1 => "22",
+        2 => "23",
+        3 => "24",
+        4 => "25",
+        5 => "26",
+        6 => "27",
+        7 => "28",
+        8 => "29",
+        9 => "30",
+        10 => "31",
+        11 => "32",
+        12 => "33",
+        13 => "34",
+        14 => "35",
+        15 => "36",
+        16 => "37",
+        17 => "38",
+        18 => "39",
+        19 => "40",
+        20 => "41",
+        21 => "42",
+        22 => "43",
+        23 => "44",
+        24 => "45",
+        25 => "46",
+        26 => "47",
+        27 => "48",
+        _ => "Unknown Partition",
+    };
+    partition_name.to_string()
+}
+
+/// Malformed lines are skipped, `check_set_tables` is what reports them.
+pub fn parse_set_data_into_hashmap() -> HashMap<u16, &'static str> {
+    let mut lookup_table = HashMap::new();
+    let data = include_str!("../../../data/set_data.csv");
+    for line in data.lines() {
+        let Some((id, name)) = line.split_once(',') else {
+            continue;
+        };
+        let name = set_name_field(name);
+        if let Ok(key) = id.trim().parse::<u16>()
+            && !name.is_empty()
+        {
+            lookup_table.insert(key, name);
+        }
+    }
+
+    lookup_table
+}
+
+/// Malformed values are skipped, `check_set_tables` is what reports them.
+pub fn parse_set_ids_into_hashmap() -> HashMap<u32, u16> {
+    let mut report = IngestReport::new(IngestMode::Lenient);
+    parse_set_ids_text(include_str!("../../../data/set_ids.csv"), Path::new("data/set_ids.csv"), &mut report)
+        .unwrap_or_default()
+}
+
+/// Some names in `set_data.csv` carry a stray trailing comma.
+fn set_name_field(name: &str) -> &str {
+    name.trim().trim_end_matches(',').trim()
+}
+
+pub fn parse_set_ids_text(data: &str, file: &Path, report: &mut IngestReport) -> Result<HashMap<u32, u16>, IngestError> {
+    let mut lookup_table: HashMap<u32, u16> = HashMap::new();
+
+    for (line_no, line) in data.lines().enumerate() {
+        let parts: Vec<&str> = line.split(',').collect();
+        if parts.is_empty() || line.trim().is_empty() {
+            continue;
+        }
+
+        let value_str = parts[0];
+        let Ok(value_num) = value_str.parse::<u16>() else {
+            let error = FieldError::Invalid { field: "set id", value: value_str.to_string() };
+            report.record(IngestError::Parse { file: file.to_path_buf(), line: line_no + 1, error })?;
+            continue;
+        };
+
+        for key_str in &parts[1..] {
+            let Ok(key_num) = key_str.trim().parse::<u32>() else {
+                let error = FieldError::Invalid { field: "item id", value: key_str.to_string() };
+                report.record(IngestError::Parse { file: file.to_path_buf(), line: line_no + 1, error })?;
+                continue;
+            };
+
+            lookup_table.insert(key_num, value_num);
+        }
+    }
+
+    Ok(lookup_table)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct TableRow {
+    pub zone_id: u8,
+    pub boss_id: u8,
+    pub partition_id: u8,
+    pub ranking: u8,
+    /// reference into master.players by id
+    pub player_id: u32,
+    /// reference into master.characters by id, 0 if unknown
+    pub character_id: u32,
+    pub dps: u32,
+    pub boss: bool,
+    /// milliseconds since the unix epoch
+    pub timestamp: Option<u64>,
+    /// slotted skills in bar order
+    pub skills: Vec<SkillSlot>,
+    /// worn pieces in slot order
+    pub gear: Vec<GearPiece>,
+    pub front_bar: WeaponType,
+    pub back_bar: WeaponType,
+    pub armour_weights: ArmourWeights,
+    /// classes of the slotted skills, main class first, see `infer_classes`
+    pub classes: Vec<Class>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct GearPiece {
+    /// position in the scraped gear list, see `gear_slot_name`
+    pub slot: u8,
+    /// reference into master.sets by id, 0 if the item id isn't mapped
+    pub set_id: u16,
+    pub item_id: u32,
+    /// reference into master.icons by id, 0 if unknown
+    pub icon_id: u32,
+}
+
+/// A ranking slot on an esologs page. Each one appears in the table at most once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RowKey {
+    pub boss_id: u8,
+    pub partition_id: u8,
+    pub ranking: u8,
+    pub boss: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct SkillSlot {
+    /// reference into master.skills by id
+    pub skill_id: u16,
+    /// position in the scraped talent list, 0..6 on the front bar and 6..12
+    /// on the back bar, with the ultimate last on each
+    pub slot: u8,
+}
+
+impl SkillSlot {
+    /// 1 for the front bar, 2 for the back bar
+    pub fn bar(&self) -> u8 {
+        self.slot / 6 + 1
+    }
+
+    /// 1 to 6 within the bar
+    pub fn position(&self) -> u8 {
+        self.slot % 6 + 1
+    }
+
+    pub fn is_ultimate(&self) -> bool {
+        self.slot == ULTIMATE_SLOTS.0 || self.slot == ULTIMATE_SLOTS.1
+    }
+}
+
+/// Talent list positions of the front and back bar ultimates.
+pub const ULTIMATE_SLOTS: (u8, u8) = (5, 11);
+
+impl TableRow {
+    /// Every slotted skill in bar order.
+    pub fn skill_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        self.skills.iter().map(|s| s.skill_id)
+    }
+
+    /// Ultimate on bar 1 or 2, `None` if that slot was empty.
+    pub fn ultimate(&self, bar: u8) -> Option<u16> {
+        let slot = if bar == 1 { ULTIMATE_SLOTS.0 } else { ULTIMATE_SLOTS.1 };
+        self.skills.iter().find(|s| s.slot == slot).map(|s| s.skill_id)
+    }
+
+    /// Set of every worn piece in slot order, 0 for unmapped items.
+    pub fn set_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        self.gear.iter().map(|g| g.set_id)
+    }
+
+    /// The class most of the slotted class skills belong to.
+    pub fn main_class(&self) -> Option<Class> {
+        self.classes.first().copied()
+    }
+
+    /// Whether skills from more than one class are slotted.
+    pub fn is_subclassed(&self) -> bool {
+        self.classes.len() > 1
+    }
+
+    pub fn key(&self) -> RowKey {
+        RowKey {
+            boss_id: self.boss_id,
+            partition_id: self.partition_id,
+            ranking: self.ranking,
+            boss: self.boss,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct Player {
+    pub id: u32,
+    pub name: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct Character {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct Skill {
+    pub id: u16,
+    pub name: String,
+
+    pub class: Option<String>,
+    pub tree: Option<String>,
+    pub display_name: Option<String>,
+    /// name of the unmorphed skill, when the metadata knows it
+    pub base_skill: Option<String>,
+}
+
+/// An entry of `skills.json`, whose keys are camelCase.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillMetadata {
+    pub file_name: String,
+    pub skill_name: String,
+    pub skill_class: String,
+    pub skill_tree: String,
+    #[serde(default)]
+    pub base_skill: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct ItemSet {
+    pub id: u16,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Encode, Decode)]
+pub struct MasterTable {
+    pub rows: Vec<TableRow>,
+    pub players: Vec<Player>,
+    pub characters: Vec<Character>,
+    pub skills: Vec<Skill>,
+    pub sets: Vec<ItemSet>,
+    pub icons: Vec<Icon>,
+    /// built on load, see `MasterTable::reindex`
+    #[serde(skip)]
+    #[bitcode(skip)]
+    pub index: TableIndex,
+}
+
+/// Gear icon path, shared by every piece that uses it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct Icon {
+    pub id: u32,
+    pub path: String,
+}
+
+/// Name of a position in the scraped gear list, which follows the esologs
+/// equipment order.
+pub fn gear_slot_name(slot: u8) -> &'static str {
+    match slot {
+        0 => "Head",
+        1 => "Neck",
+        2 => "Chest",
+        3 => "Shoulders",
+        4 => "Main Hand",
+        5 => "Off Hand",
+        6 => "Waist",
+        7 => "Legs",
+        8 => "Feet",
+        9 => "Ring 1",
+        10 => "Ring 2",
+        11 => "Hands",
+        12 => "Backup Main Hand",
+        13 => "Backup Off Hand",
+        _ => "Unknown",
+    }
+}
+
+pub const FRONT_BAR_SLOTS: (u8, u8) = (4, 5);
+pub const BACK_BAR_SLOTS: (u8, u8) = (12, 13);
+
+/// What is equipped on one weapon bar.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Encode, Decode)]
+pub enum WeaponType {
+    #[default]
+    Unknown,
+    DualWield,
+    TwoHanded,
+    OneHandAndShield,
+    Bow,
+    DestructionStaff,
+    RestorationStaff,
+}
+
+impl WeaponType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponType::Unknown => "Unknown",
+            WeaponType::DualWield => "Dual Wield",
+            WeaponType::TwoHanded => "Two Handed",
+            WeaponType::OneHandAndShield => "One Hand and Shield",
+            WeaponType::Bow => "Bow",
+            WeaponType::DestructionStaff => "Destruction Staff",
+            WeaponType::RestorationStaff => "Restoration Staff",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WeaponPiece {
+    OneHanded,
+    TwoHanded,
+    Shield,
+    Bow,
+    DestructionStaff,
+    RestorationStaff,
+}
+
+/// Weapon icons are named after the weapon, e.g. `gear_breton_1hsword_d`,
+/// `gear_altmer_bow_d` or `gear_altmer_staff_d`. Only whole `_` separated
+/// parts of the file name count, so `gear_elbow_d` isn't a bow. Anything else
+/// isn't a weapon.
+fn weapon_piece_from_icon(icon: &str) -> Option<WeaponPiece> {
+    let icon = icon.to_lowercase();
+    let file = icon.rsplit('/').next().unwrap_or(&icon);
+    let stem = file.split_once('.').map_or(file, |(stem, _)| stem);
+    let parts: Vec<&str> = stem.split('_').collect();
+
+    let healing = parts.iter().any(|p| matches!(*p, "heal" | "healing" | "resto" | "restoration"));
+    parts.iter().find_map(|part| match *part {
+        "shield" => Some(WeaponPiece::Shield),
+        "bow" => Some(WeaponPiece::Bow),
+        "healstaff" | "healingstaff" | "restostaff" | "restorationstaff" => Some(WeaponPiece::RestorationStaff),
+        "staff" if healing => Some(WeaponPiece::RestorationStaff),
+        "staff" => Some(WeaponPiece::DestructionStaff),
+        "dagger" => Some(WeaponPiece::OneHanded),
+        p if p.len() > 2 && p.starts_with("2h") => Some(WeaponPiece::TwoHanded),
+        p if p.len() > 2 && p.starts_with("1h") => Some(WeaponPiece::OneHanded),
+        _ => None,
+    })
+}
+
+/// Classifies a bar from the icons in its main and off hand slots.
+pub fn weapon_type_from_icons(main_hand: Option<&str>, off_hand: Option<&str>) -> WeaponType {
+    let main_hand = main_hand.and_then(weapon_piece_from_icon);
+    let off_hand = off_hand.and_then(weapon_piece_from_icon);
+
+    match (main_hand, off_hand) {
+        (Some(WeaponPiece::Bow), _) => WeaponType::Bow,
+        (Some(WeaponPiece::DestructionStaff), _) => WeaponType::DestructionStaff,
+        (Some(WeaponPiece::RestorationStaff), _) => WeaponType::RestorationStaff,
+        (Some(WeaponPiece::TwoHanded), _) => WeaponType::TwoHanded,
+        (Some(WeaponPiece::OneHanded), Some(WeaponPiece::OneHanded)) => WeaponType::DualWield,
+        (Some(WeaponPiece::OneHanded), Some(WeaponPiece::Shield)) => WeaponType::OneHandAndShield,
+        _ => WeaponType::Unknown,
+    }
+}
+
+/// Gear slots that take an armour piece rather than jewelry or a weapon.
+pub const BODY_SLOTS: [u8; 7] = [0, 2, 3, 6, 7, 8, 11];
+
+/// Number of body pieces of each weight a row wears.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Encode, Decode)]
+pub struct ArmourWeights {
+    pub light: u8,
+    pub medium: u8,
+    pub heavy: u8,
+}
+
+impl ArmourWeights {
+    /// Counts the body pieces whose icon names a weight, e.g.
+    /// `gear_breton_medium_chest_d`. Like the weapon icons only whole `_`
+    /// separated parts count.
+    pub fn from_icons<'a>(body_icons: impl Iterator<Item = &'a str>) -> ArmourWeights {
+        let mut weights = ArmourWeights::default();
+        for icon in body_icons {
+            let icon = icon.to_lowercase();
+            let file = icon.rsplit('/').next().unwrap_or(&icon);
+            let stem = file.split_once('.').map_or(file, |(stem, _)| stem);
+            for part in stem.split('_') {
+                let count = match part {
+                    "light" => &mut weights.light,
+                    "medium" => &mut weights.medium,
+                    "heavy" => &mut weights.heavy,
+                    _ => continue,
+                };
+                *count += 1;
+                break;
+            }
+        }
+        weights
+    }
+
+    pub fn total(&self) -> u8 {
+        self.light + self.medium + self.heavy
+    }
+
+    /// e.g. "5 Medium / 2 Light", heaviest share first.
+    pub fn label(&self) -> String {
+        let mut parts = vec![(self.light, "Light"), (self.medium, "Medium"), (self.heavy, "Heavy")];
+        parts.retain(|(count, _)| *count > 0);
+        parts.sort_by_key(|p| std::cmp::Reverse(p.0));
+
+        if parts.is_empty() {
+            return "No Armour".to_string();
+        }
+        parts.iter().map(|(count, name)| format!("{} {}", count, name)).collect::<Vec<_>>().join(" / ")
+    }
+}
+
+/// A class whose skill lines can be slotted. Since subclassing a build can
+/// mix skill lines from up to three classes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Encode, Decode)]
+pub enum Class {
+    Arcanist,
+    Dragonknight,
+    Necromancer,
+    Nightblade,
+    Sorcerer,
+    Templar,
+    Warden,
+}
+
+impl Class {
+    pub const ALL: [Class; 7] = [
+        Class::Arcanist,
+        Class::Dragonknight,
+        Class::Necromancer,
+        Class::Nightblade,
+        Class::Sorcerer,
+        Class::Templar,
+        Class::Warden,
+    ];
+
+    /// Also the `skillClass` used for the class's skills in the metadata.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Class::Arcanist => "Arcanist",
+            Class::Dragonknight => "Dragonknight",
+            Class::Necromancer => "Necromancer",
+            Class::Nightblade => "Nightblade",
+            Class::Sorcerer => "Sorcerer",
+            Class::Templar => "Templar",
+            Class::Warden => "Warden",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Class> {
+        Class::ALL.into_iter().find(|c| c.name() == name)
+    }
+}
+
+/// Classes of the slotted class skills, the one with the most skills first
+/// and any subclassed ones after it in class order. Needs the skills in
+/// `master` to be indexed.
+pub fn infer_classes(master: &MasterTable, slots: &[SkillSlot]) -> Vec<Class> {
+    let mut counts: BTreeMap<Class, u32> = BTreeMap::new();
+    for slot in slots {
+        let class = master
+            .skill(slot.skill_id)
+            .and_then(|s| s.class.as_deref())
+            .and_then(Class::from_name);
+        if let Some(class) = class {
+            *counts.entry(class).or_insert(0) += 1;
+        }
+    }
+
+    // ties go to the first class in class order
+    let Some(main) = counts.iter().max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0))).map(|(class, _)| *class) else {
+        return Vec::new();
+    };
+
+    let mut classes = vec![main];
+    classes.extend(counts.into_keys().filter(|c| *c != main));
+    classes
+}
+
+impl MasterTable {
+    /// Reindexes the table and fills in `TableRow.classes` from the skills.
+    pub(crate) fn infer_row_classes(&mut self) {
+        self.reindex();
+        let classes: Vec<ClassCombo> = self.rows.iter().map(|r| infer_classes(self, &r.skills)).collect();
+        for (row, classes) in self.rows.iter_mut().zip(classes) {
+            row.classes = classes;
+        }
+    }
+}
+
+/// Classes of a row as kept in `TableRow.classes`, main class first.
+pub type ClassCombo = Vec<Class>;
+
+/// e.g. "Arcanist" or "Arcanist + Nightblade", main class first.
+pub fn class_combo_label(classes: &[Class]) -> String {
+    if classes.is_empty() {
+        return "Unknown".to_string();
+    }
+    classes.iter().map(|c| c.name()).collect::<Vec<_>>().join(" + ")
+}
+
+pub fn parse_set_data_text(text: &str, file: &Path, report: &mut IngestReport) -> Result<Vec<ItemSet>, IngestError> {
+    let mut sets_vec: Vec<ItemSet> = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.splitn(2, ',');
+        let id_str = parts.next().map(str::trim).unwrap_or("");
+        let name = parts.next().map(set_name_field).filter(|s| !s.is_empty());
+
+        let error = match (id_str.parse::<u16>(), name) {
+            (Ok(id), Some(name)) => {
+                sets_vec.push(ItemSet { id, name: name.to_string() });
+                continue;
+            }
+            (Err(_), _) => FieldError::Invalid { field: "set id", value: id_str.to_string() },
+            (Ok(_), None) => FieldError::Missing { field: "set name" },
+        };
+        report.record(IngestError::Parse { file: file.to_path_buf(), line: line_no + 1, error })?;
+    }
+    Ok(sets_vec)
+}
+
+/// Disagreements between `set_data.csv` and `set_ids.csv`. Malformed values
+/// are reported by the parsers instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetTableIssue {
+    /// set in `set_data.csv` with no item ids in `set_ids.csv`
+    NoItemIds { set_id: u16, name: String },
+    /// set id in `set_ids.csv` with no name in `set_data.csv`
+    NoName { set_id: u16 },
+    /// set id named twice in `set_data.csv`
+    DuplicateSetData { set_id: u16 },
+    /// set id on two lines of `set_ids.csv`
+    DuplicateSetIds { set_id: u16 },
+    ItemInTwoSets { item_id: u32, first: u16, second: u16 },
+    DuplicateName { name: String, set_ids: Vec<u16> },
+    /// "Perfected X" without a set named "X"
+    PerfectedWithoutBase { set_id: u16, name: String },
+}
+
+impl fmt::Display for SetTableIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetTableIssue::NoItemIds { set_id, name } => write!(f, "set {} ({}) has no item ids", set_id, name),
+            SetTableIssue::NoName { set_id } => write!(f, "set {} has item ids but no name", set_id),
+            SetTableIssue::DuplicateSetData { set_id } => write!(f, "set {} is named more than once", set_id),
+            SetTableIssue::DuplicateSetIds { set_id } => write!(f, "set {} has more than one item id line", set_id),
+            SetTableIssue::ItemInTwoSets { item_id, first, second } => {
+                write!(f, "item {} is mapped to sets {} and {}", item_id, first, second)
+            }
+            SetTableIssue::DuplicateName { name, set_ids } => {
+                let ids: Vec<String> = set_ids.iter().map(u16::to_string).collect();
+                write!(f, "'{}' is the name of sets {}", name, ids.join(", "))
+            }
+            SetTableIssue::PerfectedWithoutBase { set_id, name } => {
+                write!(f, "set {} ({}) has no non-Perfected version", set_id, name)
+            }
+        }
+    }
+}
+
+/// Cross-checks the parsed set names against the raw `set_ids.csv` text,
+/// which keeps the duplicates that `parse_set_ids_text` folds away.
+pub fn check_set_tables(sets: &[ItemSet], set_ids_text: &str) -> Vec<SetTableIssue> {
+    let mut issues = Vec::new();
+
+    let mut names: BTreeMap<u16, &str> = BTreeMap::new();
+    let mut ids_by_name: BTreeMap<&str, Vec<u16>> = BTreeMap::new();
+    for set in sets {
+        if names.contains_key(&set.id) {
+            issues.push(SetTableIssue::DuplicateSetData { set_id: set.id });
+            continue;
+        }
+        names.insert(set.id, &set.name);
+        ids_by_name.entry(&set.name).or_default().push(set.id);
+    }
+
+    let mut item_sets: HashMap<u32, u16> = HashMap::new();
+    let mut with_items: BTreeMap<u16, bool> = BTreeMap::new();
+    for line in set_ids_text.lines() {
+        let mut parts = line.split(',');
+        let Some(Ok(set_id)) = parts.next().map(|p| p.trim().parse::<u16>()) else {
+            continue;
+        };
+        let item_ids: Vec<u32> = parts.filter_map(|p| p.trim().parse().ok()).collect();
+
+        if with_items.insert(set_id, !item_ids.is_empty()).is_some() {
+            issues.push(SetTableIssue::DuplicateSetIds { set_id });
+        }
+        for item_id in item_ids {
+            match item_sets.get(&item_id) {
+                Some(&first) if first != set_id => {
+                    issues.push(SetTableIssue::ItemInTwoSets { item_id, first, second: set_id });
+                }
+                Some(_) => {}
+                None => {
+                    item_sets.insert(item_id, set_id);
+                }
+            }
+        }
+    }
+
+    for (&set_id, &name) in &names {
+        if !with_items.get(&set_id).copied().unwrap_or(false) {
+            issues.push(SetTableIssue::NoItemIds { set_id, name: name.to_string() });
+        }
+        if let Some(base) = name.strip_prefix("Perfected ")
+            && !ids_by_name.contains_key(base)
+        {
+            issues.push(SetTableIssue::PerfectedWithoutBase { set_id, name: name.to_string() });
+        }
+    }
+    for &set_id in with_items.keys() {
+        if !names.contains_key(&set_id) {
+            issues.push(SetTableIssue::NoName { set_id });
+        }
+    }
+    for (name, set_ids) in ids_by_name {
+        if set_ids.len() > 1 {
+            issues.push(SetTableIssue::DuplicateName { name: name.to_string(), set_ids });
+        }
+    }
+
+    issues
+}
+
+/// Set whose name appears as whole words in an item name, e.g. "Ring of the
+/// Pale Order". The longest matching name wins, and a "Perfected ..." item
+/// gets the Perfected version of the set when there is one.
+pub fn set_for_item_name(item_name: &str, sets: &[ItemSet]) -> Option<u16> {
+    let item_name = item_name.to_lowercase();
+    let item_perfected = item_name.starts_with("perfected ");
+
+    sets.iter()
+        .filter_map(|set| {
+            let name = set.name.to_lowercase();
+            let base = name.strip_prefix("perfected ").unwrap_or(&name);
+            let perfected = base.len() != name.len();
+            contains_words(&item_name, base).then_some((base.len(), perfected == item_perfected, set.id))
+        })
+        .max()
+        .map(|(_, _, id)| id)
+}
+
+/// Whether `needle` appears in `haystack` with no letter or digit directly
+/// before or after it, so "Order" doesn't match "Border".
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    !needle.is_empty()
+        && haystack.match_indices(needle).any(|(start, _)| {
+            let before = haystack[..start].chars().next_back();
+            let after = haystack[start + needle.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+}
+
+/// Lines to append to `set_ids.csv` for the unmapped ids that matched a set
+/// by name, one `set_id,item_id,...` line per set.
+pub fn set_ids_patch(unmapped: &[UnmappedGear]) -> String {
+    let mut per_set: BTreeMap<u16, Vec<u32>> = BTreeMap::new();
+    for gear in unmapped {
+        if let Some(set_id) = gear.suggested_set {
+            per_set.entry(set_id).or_default().push(gear.item_id);
+        }
+    }
+
+    let mut patch = String::new();
+    for (set_id, mut item_ids) in per_set {
+        item_ids.sort();
+        let ids: Vec<String> = item_ids.iter().map(u32::to_string).collect();
+        patch.push_str(&format!("{},{}\n", set_id, ids.join(",")));
+    }
+    patch
+}
+
+pub(crate) fn parse_skill_metadata(text: &str, file: &Path, report: &mut IngestReport) -> Result<HashMap<String, SkillMetadata>, IngestError> {
+    let raw_meta: HashMap<String, SkillMetadata> = serde_json::from_str(text)
+        .map_err(|e| IngestError::Json { file: file.to_path_buf(), message: e.to_string() })?;
+
+    let mut meta_lookup: HashMap<String, SkillMetadata> = HashMap::new();
+
+    for (key, meta) in raw_meta {
+        let Some(f) = meta
+            .file_name
+            .strip_suffix(".png")
+            .and_then(|f| f.strip_prefix("ability_"))
+        else {
+            report.record(IngestError::Json {
+                file: file.to_path_buf(),
+                message: format!("unexpected fileName '{}' for skill entry {}", meta.file_name, key),
+            })?;
+            continue;
+        };
+        meta_lookup.insert(f.to_string(), meta);
+    }
+
+    Ok(meta_lookup)
+}
+
+/// ESO's `SKILL_TYPE_*` values, named the way `skills.json` names them.
+fn skill_type_name(skill_type: &str) -> Option<&str> {
+    match skill_type {
+        "2" => Some("Weapon"),
+        "3" => Some("Armor"),
+        "4" => Some("World"),
+        "5" => Some("Guild"),
+        "6" => Some("Alliance War"),
+        "7" => Some("Racial"),
+        "8" => Some("Craft"),
+        // class skills take their class from `classType` instead
+        other if other.parse::<u8>().is_ok() => None,
+        "" => None,
+        other => Some(other),
+    }
+}
+
+/// Reads a UESP style `minedSkills` CSV export. Columns are found by their
+/// header: `texture` and `name` are required, `classType`, `skillType`,
+/// `skillLine` and `baseName` are used when present. Rows for the same icon
+/// keep the first entry, exports list every rank of a skill.
+pub fn parse_mined_skills_text(text: &str, file: &Path, report: &mut IngestReport) -> Result<HashMap<String, SkillMetadata>, IngestError> {
+    let mut lines = text.lines().enumerate();
+    let header = lines.next().map(|(_, line)| split_csv_line(line)).unwrap_or_default();
+    let column = |names: &[&str]| header.iter().position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)));
+
+    let (Some(texture_col), Some(name_col)) = (column(&["texture", "icon", "fileName"]), column(&["name", "skillName"])) else {
+        let error = FieldError::Missing { field: "texture and name columns" };
+        report.record(IngestError::Parse { file: file.to_path_buf(), line: 1, error })?;
+        return Ok(HashMap::new());
+    };
+    let class_col = column(&["classType", "skillClass"]);
+    let type_col = column(&["skillType"]);
+    let line_col = column(&["skillLine", "skillTree"]);
+    let base_col = column(&["baseName", "baseSkill"]);
+
+    let mut meta_lookup: HashMap<String, SkillMetadata> = HashMap::new();
+
+    for (line_no, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line);
+        let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).map(String::as_str).filter(|v| !v.is_empty());
+
+        let (Some(texture), Some(name)) = (field(Some(texture_col)), field(Some(name_col))) else {
+            let error = FieldError::MissingFields { expected: header.len(), found: fields.len() };
+            report.record(IngestError::Parse { file: file.to_path_buf(), line: line_no + 1, error })?;
+            continue;
+        };
+
+        let icon = skill_icon_name(texture);
+        if meta_lookup.contains_key(icon) {
+            continue;
+        }
+
+        let class = field(class_col).or_else(|| field(type_col).and_then(skill_type_name)).unwrap_or("");
+        meta_lookup.insert(icon.to_string(), SkillMetadata {
+            file_name: format!("ability_{}.png", icon),
+            skill_name: name.to_string(),
+            skill_class: class.to_string(),
+            skill_tree: field(line_col).unwrap_or("").to_string(),
+            base_skill: field(base_col).map(str::to_string),
+        });
+    }
+
+    Ok(meta_lookup)
+}
+
+/// `/esoui/art/icons/ability_arcanist_018_a.dds` -> `arcanist_018_a`, the
+/// name skills are kept under.
+fn skill_icon_name(texture: &str) -> &str {
+    let file = texture.rsplit(['/', '\\']).next().unwrap_or(texture);
+    let file = file.strip_suffix(".dds").or_else(|| file.strip_suffix(".png")).unwrap_or(file);
+    file.strip_prefix("ability_").unwrap_or(file)
+}
+
+/// A slotted skill with no class, tree or name in any of the metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownSkill {
+    pub skill_id: u16,
+    /// icon name, e.g. `arcanist_018_a`
+    pub name: String,
+    /// how many times it was slotted in the table
+    pub count: usize,
+}
+
+/// Skills without metadata, most slotted first.
+pub fn unknown_skills(master: &MasterTable) -> Vec<UnknownSkill> {
+    let mut counts: HashMap<u16, usize> = master
+        .skills
+        .iter()
+        .filter(|s| s.display_name.is_none())
+        .map(|s| (s.id, 0))
+        .collect();
+
+    for row in &master.rows {
+        for skill_id in row.skill_ids() {
+            if let Some(count) = counts.get_mut(&skill_id) {
+                *count += 1;
+            }
+        }
+    }
+
+    let mut unknown: Vec<UnknownSkill> = master
+        .skills
+        .iter()
+        .filter_map(|s| counts.get(&s.id).map(|&count| UnknownSkill { skill_id: s.id, name: s.name.clone(), count }))
+        .collect();
+    unknown.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+    unknown
+}
+
+/// Hands out player and skill ids and turns parsed rows into `TableRow`s.
+/// Seeding it from an existing table keeps every id already handed out.
+pub(crate) struct TableBuilder<'a> {
+    pub(crate) master: MasterTable,
+    skill_id_map: HashMap<String, u16>,
+    pub(crate) player_id_map: HashMap<String, u32>,
+    character_id_map: HashMap<String, u32>,
+    icon_id_map: HashMap<String, u32>,
+    row_keys: HashSet<RowKey>,
+    meta_lookup: &'a HashMap<String, SkillMetadata>,
+    set_id_hashmap: &'a HashMap<u32, u16>,
+    sets: &'a [ItemSet],
+    pub(crate) unmapped_gear: HashMap<u32, UnmappedGear>,
+}
+
+impl<'a> TableBuilder<'a> {
+    pub(crate) fn new(
+        mut master: MasterTable,
+        meta_lookup: &'a HashMap<String, SkillMetadata>,
+        set_id_hashmap: &'a HashMap<u32, u16>,
+        sets: &'a [ItemSet],
+    ) -> Self {
+        // skills that were unknown when first seen, or that come from a table
+        // migrated from before base skills were kept, pick up newer metadata
+        for skill in master.skills.iter_mut() {
+            let Some(meta) = meta_lookup.get(&skill.name) else {
+                continue;
+            };
+            if skill.display_name.is_none() {
+                skill.class = Some(meta.skill_class.clone());
+                skill.tree = Some(meta.skill_tree.clone());
+                skill.display_name = Some(meta.skill_name.clone());
+            }
+            if skill.base_skill.is_none() {
+                skill.base_skill = meta.base_skill.clone();
+            }
+        }
+        master.infer_row_classes();
+
+        let skill_id_map = master.skills.iter().map(|s| (s.name.clone(), s.id)).collect();
+        let player_id_map = master.players.iter().map(|p| (p.name.clone(), p.id)).collect();
+        let character_id_map = master.characters.iter().map(|c| (c.name.clone(), c.id)).collect();
+        let icon_id_map = master.icons.iter().map(|i| (i.path.clone(), i.id)).collect();
+        let row_keys = master.rows.iter().map(TableRow::key).collect();
+
+        TableBuilder {
+            master,
+            skill_id_map,
+            player_id_map,
+            character_id_map,
+            icon_id_map,
+            row_keys,
+            meta_lookup,
+            set_id_hashmap,
+            sets,
+            unmapped_gear: HashMap::new(),
+        }
+    }
+
+    fn get_skill_id(&mut self, name: String) -> u16 {
+        if let Some(&id) = self.skill_id_map.get(&name) {
+            return id;
+        }
+
+        let new_id = self.master.skills.len() as u16 + 1;
+
+        let meta = self.meta_lookup.get(&name);
+
+        self.master.push_skill(Skill {
+            id: new_id,
+            name: name.clone(),
+            class: meta.map(|m| m.skill_class.clone()),
+            tree: meta.map(|m| m.skill_tree.clone()),
+            display_name: meta.map(|m| m.skill_name.clone()),
+            base_skill: meta.and_then(|m| m.base_skill.clone()),
+        });
+
+        self.skill_id_map.insert(name, new_id);
+        new_id
+    }
+
+    fn get_player_id(&mut self, name: String) -> u32 {
+        if let Some(&id) = self.player_id_map.get(&name) {
+            return id;
+        }
+        let new_id = self.master.players.len() as u32 + 1;
+        self.player_id_map.insert(name.clone(), new_id);
+
+        self.master.players.push(Player {
+            id: new_id,
+            name,
+            text: "".into(),
+        });
+
+        new_id
+    }
+
+    fn get_icon_id(&mut self, path: &str) -> u32 {
+        if path.is_empty() {
+            return 0;
+        }
+        if let Some(&id) = self.icon_id_map.get(path) {
+            return id;
+        }
+        let new_id = self.master.icons.len() as u32 + 1;
+        self.icon_id_map.insert(path.to_string(), new_id);
+
+        self.master.icons.push(Icon {
+            id: new_id,
+            path: path.to_string(),
+        });
+
+        new_id
+    }
+
+    fn get_character_id(&mut self, name: &str) -> u32 {
+        if name.is_empty() {
+            return 0;
+        }
+        if let Some(&id) = self.character_id_map.get(name) {
+            return id;
+        }
+        let new_id = self.master.characters.len() as u32 + 1;
+        self.character_id_map.insert(name.to_string(), new_id);
+
+        self.master.characters.push(Character {
+            id: new_id,
+            name: name.to_string(),
+        });
+
+        new_id
+    }
+
+    /// Unmapped ids get 0 and are noted, with the set their name matches, so
+    /// they can be reviewed and added to `set_ids.csv`.
+    fn get_set_id(&mut self, item: &GearItem) -> u16 {
+        if let Some(&id) = self.set_id_hashmap.get(&item.id) {
+            return id;
+        }
+
+        let sets = self.sets;
+        let unmapped = self.unmapped_gear.entry(item.id).or_insert_with(|| UnmappedGear {
+            item_id: item.id,
+            name: item.name.clone(),
+            count: 0,
+            suggested_set: set_for_item_name(&item.name, sets),
+        });
+        unmapped.count += 1;
+        0
+    }
+
+    /// Rows are validated by `parse_csv_line`, so ranking and ids are known to
+    /// fit. A row whose key is already in the table is refused.
+    pub(crate) fn process_entry(&mut self, entry: &Row, boss: bool, file: &Path, report: &mut IngestReport) -> Result<(), IngestError> {
+        let ranking: u8 = ranking_from_row_id(&entry.row_id).unwrap_or_default();
+
+        let zone_id: u8 = entry.zone as u8;
+        let boss_id: u8 = entry.boss as u8;
+        let partition_id: u8 = entry.partition as u8;
+
+        let key = RowKey { boss_id, partition_id, ranking, boss };
+        if self.row_keys.contains(&key) {
+            return report.skip_row(IngestError::Duplicate { file: file.to_path_buf(), key });
+        }
+
+        let skills: Vec<SkillSlot> = entry.talents.iter().enumerate().filter(|(_, e)| !e.is_empty()).map(|(slot, e)| {
+            // the scraper keeps the full icon url, merged files only the file name
+            let e = e.rsplit('/').next().unwrap_or(e);
+            let e = e.strip_suffix(".png").unwrap_or(e);
+            let e = e.strip_prefix("ability_").unwrap_or(e);
+            let skill_name = e.to_string();
+            SkillSlot { skill_id: self.get_skill_id(skill_name), slot: slot as u8 }
+        }).collect();
+
+        let gear: Vec<GearPiece> = entry.gear.iter().map(|g| GearPiece {
+            slot: g.slot,
+            set_id: self.get_set_id(g),
+            item_id: g.id,
+            icon_id: self.get_icon_id(&g.icon),
+        }).collect();
+
+        let dps: u32 = entry.dps as u32;
+
+        let canonical_name = entry.at_name.strip_prefix("@").unwrap_or(&entry.at_name).to_string();
+        let player_id: u32 = self.get_player_id(canonical_name);
+        let character_id: u32 = self.get_character_id(entry.name.trim());
+
+        let bar = |(main_slot, off_slot): (u8, u8)| {
+            let icon = |slot| entry.gear.iter().find(|g| g.slot == slot).map(|g| g.icon.as_str());
+            weapon_type_from_icons(icon(main_slot), icon(off_slot))
+        };
+        let front_bar = bar(FRONT_BAR_SLOTS);
+        let back_bar = bar(BACK_BAR_SLOTS);
+        let armour_weights = ArmourWeights::from_icons(
+            entry.gear.iter().filter(|g| BODY_SLOTS.contains(&g.slot)).map(|g| g.icon.as_str()),
+        );
+        let classes = infer_classes(&self.master, &skills);
+
+        let row = TableRow {
+            zone_id,
+            boss_id,
+            partition_id,
+            ranking,
+            player_id,
+            character_id,
+            dps,
+            boss,
+            timestamp: entry.timestamp,
+            skills,
+            gear,
+            front_bar,
+            back_bar,
+            armour_weights,
+            classes,
+        };
+
+        self.row_keys.insert(key);
+        self.master.rows.push(row);
+        Ok(())
+    }
+}
+
+/// Decodes a `master_table.bin` of any supported version, see `format`.
+pub fn decode_master_table(bytes: &[u8]) -> Result<MasterTable, FormatError> {
+    let mut master = format::decode(bytes)?;
+    master.reindex();
+    Ok(master)
+}
+
+/// Most slotted skills, counting every slot so a skill on both bars counts
+/// twice.
+pub fn top_n_skills_for_partitions(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<(Skill, u32)> {
+    let query = Query { limit: Some(n), ..Query::new(GroupBy::SkillSlot, Measure::Count, filter) };
+    skill_results(master, &query).into_iter().map(|(skill, count)| (skill, count as u32)).collect()
+}
+
+pub fn top_n_skills_for_partitions_normalised(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<(Skill, u32)> {
+    let query = Query { limit: Some(n), ..Query::new(GroupBy::SkillSlot, Measure::NormalisedCount, filter) };
+    skill_results(master, &query).into_iter().map(|(skill, count)| (skill, count.round() as u32)).collect()
+}
+
+pub fn top_n_sets_for_partitions(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<(ItemSet, u32)> {
+    let query = Query { limit: Some(n), ..Query::new(GroupBy::Set, Measure::Count, filter) };
+    set_results(master, &query).into_iter().map(|(set, count)| (set, count as u32)).collect()
+}
+
+pub fn top_n_sets_for_partitions_normalised(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<(ItemSet, u32)> {
+    let query = Query { limit: Some(n), ..Query::new(GroupBy::Set, Measure::NormalisedCount, filter) };
+    set_results(master, &query).into_iter().map(|(set, count)| (set, count.round() as u32)).collect()
+}
+
+/// Most worn sets in one gear slot, with the number of rows wearing each.
+/// Perfected and non-Perfected versions count as one set.
+pub fn top_n_sets_for_slot(master: &MasterTable, filter: &RowFilter, slot: u8, n: usize) -> Vec<(ItemSet, u32)> {
+    let canonical_id = canonical_set_ids(master);
+
+    let mut freq: HashMap<u16, u32> = HashMap::new();
+    for row in filter.rows(master) {
+        for piece in row.gear.iter().filter(|g| g.slot == slot && g.set_id != 0) {
+            let id = canonical_id.get(&piece.set_id).copied().unwrap_or(piece.set_id);
+            *freq.entry(id).or_insert(0) += 1;
+        }
+    }
+
+    let mut set_counts: Vec<(u16, u32)> = freq.into_iter().collect();
+    set_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    set_counts
+        .into_iter()
+        .take(n)
+        .map(|(id, count)| {
+            let set = master
+                .set(id)
+                .cloned()
+                .unwrap_or_else(|| ItemSet { id, name: format!("Unknown ({})", id) });
+            (set, count)
+        })
+        .collect()
+}
+
+pub fn top_n_players_by_average_rank(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<(f64, String)> {
+    let query = Query { min_rows: 20, ..Query::new(GroupBy::Player, Measure::AverageRank, filter) };
+    query
+        .run(master)
+        .into_iter()
+        .filter_map(|r| match r.key {
+            GroupKey::Player(id) => master.player(id).filter(|p| !p.name.is_empty()).map(|p| (r.value, p.name.clone())),
+            _ => None,
+        })
+        .take(n)
+        .collect()
+}
+
+pub fn top_n_players_by_top_k_count(master: &MasterTable, filter: &RowFilter, n: usize, k: u32) -> Vec<(u64, Player)> {
+    let mut total_rows: HashMap<u32, u64> = HashMap::new();
+    for row in filter.rows(master) {
+        *total_rows.entry(row.player_id).or_insert(0) += 1;
+    }
+
+    let mut counts_k: HashMap<u32, u64> = HashMap::new();
+    for row in filter.rows(master) {
+        if (row.ranking as u32) <= k {
+            *counts_k.entry(row.player_id).or_insert(0) += 1;
+        }
+    }
+
+    let mut players: Vec<(u32, u64)> = counts_k
+        .iter()
+        .map(|(pid, c)| (*pid, *c))
+        .filter(|(_, c)| *c > 0)
+        .collect();
+
+    players.sort_by_key(|p| std::cmp::Reverse(p.1));
+
+    let cutoff_count = if players.len() >= n {
+        players[n - 1].1
+    } else {
+        0
+    };
+
+    let tied_ids: Vec<u32> = players
+        .iter()
+        .filter(|(_, c)| *c == cutoff_count)
+        .map(|(pid, _)| *pid)
+        .collect();
+
+    let mut counts_km1: HashMap<u32, u64> = HashMap::new();
+
+    if k > 1 {
+        let new_k = k - 1;
+
+        for row in tied_ids.iter().flat_map(|&id| master.rows_for_player(id)) {
+            if !filter.matches(row) {
+                continue;
+            }
+            if (row.ranking as u32) <= new_k {
+                *counts_km1.entry(row.player_id).or_insert(0) += 1;
+            }
+        }
+    }
+
+    players.sort_by(|(pid_a, c_a), (pid_b, c_b)| {
+        let ord = c_b.cmp(c_a);
+        if ord != Ordering::Equal {
+            return ord;
+        }
+
+        let tka = counts_km1.get(pid_a).copied().unwrap_or(0);
+        let tkb = counts_km1.get(pid_b).copied().unwrap_or(0);
+        let ord2 = tkb.cmp(&tka);
+        if ord2 != Ordering::Equal {
+            return ord2;
+        }
+
+        let ra = total_rows.get(pid_a).copied().unwrap_or(0);
+        let rb = total_rows.get(pid_b).copied().unwrap_or(0);
+        let ord3 = rb.cmp(&ra);
+        if ord3 != Ordering::Equal {
+            return ord3;
+        }
+
+        pid_a.cmp(pid_b)
+    });
+
+    players
+        .into_iter()
+        .take(n)
+        .map(|(pid, count)| {
+            let p = master.players[pid as usize - 1].clone();
+            (count, p)
+        })
+        .filter(|(_, p)| !p.name.is_empty())
+        .collect()
+}
+
+pub fn player_rows(master: &MasterTable, player_id: u32) -> Vec<TableRow> {
+    master.rows_for_player(player_id).cloned().collect()
+}
+
+pub fn character_name(master: &MasterTable, character_id: u32) -> Option<&str> {
+    if character_id == 0 {
+        return None;
+    }
+    master
+        .characters
+        .get(character_id as usize - 1)
+        .map(|c| c.name.as_str())
+}
+
+/// Characters a player has ranked with, most used first.
+pub fn player_characters(master: &MasterTable, player_id: u32, filter: &RowFilter) -> Vec<(String, u32)> {
+    let mut counts: HashMap<u32, u32> = HashMap::new();
+    for row in master.rows_for_player(player_id) {
+        if row.character_id == 0 {
+            continue;
+        }
+        if !filter.matches(row) {
+            continue;
+        }
+        *counts.entry(row.character_id).or_insert(0) += 1;
+    }
+
+    let mut characters: Vec<(String, u32)> = counts
+        .into_iter()
+        .filter_map(|(id, count)| character_name(master, id).map(|name| (name.to_string(), count)))
+        .collect();
+
+    characters.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    characters
+}
+
+/// Maps every set id to the id that stands for it in charts, so Perfected and
+/// non-Perfected versions of a set count as one.
+pub fn canonical_set_ids(master: &MasterTable) -> HashMap<u16, u16> {
+    let mut name_to_ids: HashMap<String, Vec<(u16, bool)>> = HashMap::new();
+    for s in &master.sets {
+        let raw_name = &s.name;
+        let normalized = raw_name.strip_prefix("Perfected ").unwrap_or(raw_name).to_string();
+        let is_perfected = raw_name.starts_with("Perfected ");
+        name_to_ids.entry(normalized).or_default().push((s.id, is_perfected));
+    }
+
+    let mut canonical_id: HashMap<u16, u16> = HashMap::new();
+    for (_normalized_name, ids_vec) in name_to_ids.into_iter() {
+        let base_id = ids_vec
+            .iter()
+            .find(|(_, is_perf)| !*is_perf)
+            .map(|(id, _)| *id)
+            .or_else(|| ids_vec.first().map(|(id, _)| *id))
+            .expect("there should be at least one id for a name");
+        for (id, _) in ids_vec {
+            canonical_id.insert(id, base_id);
+        }
+    }
+
+    canonical_id
+}
+
+pub fn percent_players_with_sets(master: &MasterTable, filter: &RowFilter) -> Vec<(ItemSet, f64)> {
+    set_results(master, &Query::new(GroupBy::Set, Measure::Share, filter))
+}
+
+/// Runs a set query, with "Other" as set 999.
+fn set_results(master: &MasterTable, query: &Query) -> Vec<(ItemSet, f64)> {
+    query
+        .run(master)
+        .into_iter()
+        .map(|r| {
+            let set = match r.key {
+                GroupKey::Set(id) => set_or_unknown(master, id),
+                _ => ItemSet { id: 999, name: "Other".to_string() },
+            };
+            (set, r.value)
+        })
+        .collect()
+}
+
+pub(crate) fn set_or_unknown(master: &MasterTable, id: u16) -> ItemSet {
+    master.set(id).cloned().unwrap_or(ItemSet {
+        id,
+        name: format!("Unknown ({})", id),
+    })
+}
+
+/// Distinct canonical sets worn by a row, in id order.
+pub(crate) fn canonical_row_sets(row: &TableRow, canonical_id: &HashMap<u16, u16>) -> Vec<u16> {
+    let mut ids: Vec<u16> = row
+        .set_ids()
+        .filter(|&id| id != 0)
+        .map(|id| canonical_id.get(&id).copied().unwrap_or(id))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// How often `second` is worn alongside `first`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetPairing {
+    pub first: ItemSet,
+    pub second: ItemSet,
+    /// rows wearing both
+    pub count: u32,
+    /// percent of the rows wearing `first` that also wear `second`
+    pub second_given_first: f64,
+    /// how many times more often the two are worn together than they would
+    /// be if picked independently
+    pub lift: f64,
+}
+
+/// Every ordered pair of sets worn together in the filter, most common first.
+/// Perfected sets count as their base set, like `percent_players_with_sets`,
+/// and rows wearing no known set are left out.
+pub fn set_co_occurrence(master: &MasterTable, filter: &RowFilter) -> Vec<SetPairing> {
+    let canonical_id = canonical_set_ids(master);
+
+    let mut rows: u32 = 0;
+    let mut singles: HashMap<u16, u32> = HashMap::new();
+    let mut pairs: HashMap<(u16, u16), u32> = HashMap::new();
+
+    for row in filter.rows(master) {
+        let ids = canonical_row_sets(row, &canonical_id);
+        if ids.is_empty() {
+            continue;
+        }
+        rows += 1;
+
+        for (i, &a) in ids.iter().enumerate() {
+            *singles.entry(a).or_insert(0) += 1;
+            for &b in &ids[i + 1..] {
+                *pairs.entry((a, b)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut results: Vec<SetPairing> = pairs
+        .into_iter()
+        .flat_map(|((a, b), count)| [(a, b, count), (b, a, count)])
+        .map(|(a, b, count)| {
+            let with_first = singles[&a] as f64;
+            let with_second = singles[&b] as f64;
+            SetPairing {
+                first: set_or_unknown(master, a),
+                second: set_or_unknown(master, b),
+                count,
+                second_given_first: count as f64 * 100.0 / with_first,
+                lift: count as f64 * rows as f64 / (with_first * with_second),
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.second_given_first.total_cmp(&a.second_given_first))
+            .then(a.first.id.cmp(&b.first.id))
+            .then(a.second.id.cmp(&b.second.id))
+    });
+    results
+}
+
+/// Every set worn by a row, in id order.
+pub type SetCombo = Vec<ItemSet>;
+
+pub fn set_combo_label(combo: &[ItemSet]) -> String {
+    combo.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join(" + ")
+}
+
+/// Share of each full set combination among the rows, most common first.
+/// Perfected sets count as their base set and rows wearing no known set are
+/// left out.
+pub fn top_n_set_combos(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<(SetCombo, f64)> {
+    let canonical_id = canonical_set_ids(master);
+
+    shares(
+        filter
+            .rows(master)
+            .map(|row| canonical_row_sets(row, &canonical_id))
+            .filter(|ids| !ids.is_empty()),
+    )
+    .into_iter()
+    .take(n)
+    .map(|(ids, pct)| (ids.into_iter().map(|id| set_or_unknown(master, id)).collect(), pct))
+    .collect()
+}
+
+/// Front and back bar weapon types of a row.
+pub type WeaponLoadout = (WeaponType, WeaponType);
+
+pub fn weapon_loadout_label(loadout: WeaponLoadout) -> String {
+    format!("{} / {}", loadout.0.name(), loadout.1.name())
+}
+
+/// Share of each key among all keys, most common first.
+fn shares<K: Eq + Ord + std::hash::Hash>(keys: impl Iterator<Item = K>) -> Vec<(K, f64)> {
+    let mut freq: HashMap<K, u32> = HashMap::new();
+    let mut total: u32 = 0;
+
+    for key in keys {
+        total += 1;
+        *freq.entry(key).or_insert(0) += 1;
+    }
+
+    let mut results: Vec<(K, f64)> = freq
+        .into_iter()
+        .map(|(key, count)| (key, count as f64 * 100.0 / total as f64))
+        .collect();
+    results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then(a.0.cmp(&b.0)));
+    results
+}
+
+/// Share of each loadout among the rows. Rows where neither bar could be
+/// classified are left out.
+fn weapon_loadout_shares<'a>(rows: impl Iterator<Item = &'a TableRow>) -> Vec<(WeaponLoadout, f64)> {
+    shares(
+        rows.filter(|r| r.front_bar != WeaponType::Unknown || r.back_bar != WeaponType::Unknown)
+            .map(|r| (r.front_bar, r.back_bar)),
+    )
+}
+
+pub fn percent_players_with_weapon_loadouts(master: &MasterTable, filter: &RowFilter) -> Vec<(WeaponLoadout, f64)> {
+    weapon_loadout_shares(filter.rows(master))
+}
+
+/// Loadout shares for every partition in the filter (every partition when
+/// empty), in partition order.
+pub fn weapon_loadouts_per_partition(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(WeaponLoadout, f64)>)> {
+    rows_by_partition(master, filter)
+        .into_iter()
+        .map(|(p, rows)| (p, weapon_loadout_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Loadout shares for every boss seen in the partitions, in boss order.
+pub fn weapon_loadouts_per_boss(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(WeaponLoadout, f64)>)> {
+    rows_by_boss(master, filter)
+        .into_iter()
+        .map(|(b, rows)| (b, weapon_loadout_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Share of each armour weight split among the rows. Rows with no known
+/// body pieces are left out.
+fn armour_weight_shares<'a>(rows: impl Iterator<Item = &'a TableRow>) -> Vec<(ArmourWeights, f64)> {
+    shares(rows.map(|r| r.armour_weights).filter(|w| w.total() > 0))
+}
+
+pub fn percent_players_with_armour_weights(master: &MasterTable, filter: &RowFilter) -> Vec<(ArmourWeights, f64)> {
+    armour_weight_shares(filter.rows(master))
+}
+
+/// Armour weight split shares for every partition in the filter (every
+/// partition when empty), in partition order.
+pub fn armour_weights_per_partition(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(ArmourWeights, f64)>)> {
+    rows_by_partition(master, filter)
+        .into_iter()
+        .map(|(p, rows)| (p, armour_weight_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Armour weight split shares for every class seen in the partitions, by
+/// class name. Rows whose class can't be told from their skills are left out.
+pub fn armour_weights_per_class(master: &MasterTable, filter: &RowFilter) -> Vec<(String, Vec<(ArmourWeights, f64)>)> {
+    let mut per_class: BTreeMap<Class, Vec<&TableRow>> = BTreeMap::new();
+    for row in filter.rows(master) {
+        if let Some(class) = row.main_class() {
+            per_class.entry(class).or_default().push(row);
+        }
+    }
+
+    per_class
+        .into_iter()
+        .map(|(class, rows)| (class.name().to_string(), armour_weight_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Rows in the filter (every row when empty) by partition.
+fn rows_by_partition<'a>(master: &'a MasterTable, filter: &RowFilter) -> BTreeMap<u8, Vec<&'a TableRow>> {
+    let mut groups: BTreeMap<u8, Vec<&TableRow>> = BTreeMap::new();
+    for row in filter.rows(master) {
+        groups.entry(row.partition_id).or_default().push(row);
+    }
+    groups
+}
+
+/// Rows in the filter (every row when empty) by boss.
+fn rows_by_boss<'a>(master: &'a MasterTable, filter: &RowFilter) -> BTreeMap<u8, Vec<&'a TableRow>> {
+    let mut groups: BTreeMap<u8, Vec<&TableRow>> = BTreeMap::new();
+    for row in filter.rows(master) {
+        groups.entry(row.boss_id).or_default().push(row);
+    }
+    groups
+}
+
+/// Share of each main class among the rows. Rows with no class skills are
+/// left out.
+fn class_shares<'a>(rows: impl Iterator<Item = &'a TableRow>) -> Vec<(Class, f64)> {
+    shares(rows.filter_map(|r| r.main_class()))
+}
+
+/// Share of each class combination among the rows, see `infer_classes`.
+fn class_combo_shares<'a>(rows: impl Iterator<Item = &'a TableRow>) -> Vec<(ClassCombo, f64)> {
+    shares(rows.filter(|r| !r.classes.is_empty()).map(|r| r.classes.clone()))
+}
+
+pub fn percent_players_with_classes(master: &MasterTable, filter: &RowFilter) -> Vec<(Class, f64)> {
+    class_shares(filter.rows(master))
+}
+
+pub fn percent_players_with_class_combos(master: &MasterTable, filter: &RowFilter) -> Vec<(ClassCombo, f64)> {
+    class_combo_shares(filter.rows(master))
+}
+
+/// Main class shares for every partition in the filter, in partition order.
+pub fn classes_per_partition(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(Class, f64)>)> {
+    rows_by_partition(master, filter)
+        .into_iter()
+        .map(|(p, rows)| (p, class_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Main class shares for every boss seen in the partitions, in boss order.
+pub fn classes_per_boss(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(Class, f64)>)> {
+    rows_by_boss(master, filter)
+        .into_iter()
+        .map(|(b, rows)| (b, class_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Class combination shares for every partition in the filter, in partition
+/// order.
+pub fn class_combos_per_partition(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(ClassCombo, f64)>)> {
+    rows_by_partition(master, filter)
+        .into_iter()
+        .map(|(p, rows)| (p, class_combo_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Class combination shares for every boss seen in the partitions, in boss
+/// order.
+pub fn class_combos_per_boss(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, Vec<(ClassCombo, f64)>)> {
+    rows_by_boss(master, filter)
+        .into_iter()
+        .map(|(b, rows)| (b, class_combo_shares(rows.into_iter())))
+        .collect()
+}
+
+/// Percent of rows with a known class that slot skills from more than one
+/// class, for every partition in the filter.
+pub fn subclassed_percent_per_partition(master: &MasterTable, filter: &RowFilter) -> Vec<(u8, f64)> {
+    rows_by_partition(master, filter)
+        .into_iter()
+        .filter_map(|(p, rows)| {
+            let known = rows.iter().filter(|r| !r.classes.is_empty()).count();
+            let subclassed = rows.iter().filter(|r| r.is_subclassed()).count();
+            (known > 0).then(|| (p, subclassed as f64 * 100.0 / known as f64))
+        })
+        .collect()
+}
+
+pub fn percent_players_with_skills(master: &MasterTable, filter: &RowFilter) -> Vec<(Skill, f64)> {
+    skill_results(master, &Query::new(GroupBy::Skill, Measure::Share, filter))
+}
+
+/// Runs a skill query, with "Other" as skill 999.
+fn skill_results(master: &MasterTable, query: &Query) -> Vec<(Skill, f64)> {
+    query
+        .run(master)
+        .into_iter()
+        .map(|r| {
+            let skill = match r.key {
+                GroupKey::Skill(id) => skill_or_unknown(master, id),
+                _ => Skill {
+                    id: 999,
+                    name: "Other".to_string(),
+                    class: None,
+                    tree: None,
+                    display_name: Some("Other".to_string()),
+                    base_skill: None,
+                },
+            };
+            (skill, r.value)
+        })
+        .collect()
+}
+
+/// Icon name without its morph letter, `arcanist_018_a` -> `arcanist_018`
+/// and `dragonknight_017a` -> `dragonknight_017`.
+fn morph_stem(name: &str) -> Option<&str> {
+    let (last, letter) = name.char_indices().last()?;
+    if !letter.is_ascii_lowercase() {
+        return None;
+    }
+    let rest = &name[..last];
+    let rest = rest.strip_suffix('_').unwrap_or(rest);
+    rest.ends_with(|c: char| c.is_ascii_digit()).then_some(rest)
+}
+
+/// Key shared by every morph of a skill: the base skill from the metadata,
+/// else the icon name without its morph letter, else the skill on its own.
+pub fn skill_group_key(skill: &Skill) -> String {
+    match (&skill.base_skill, morph_stem(&skill.name)) {
+        (Some(base), _) => base.clone(),
+        (None, Some(stem)) => stem.to_string(),
+        (None, None) => skill.name.clone(),
+    }
+}
+
+/// A base skill and its morphs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillGroup {
+    pub key: String,
+    pub name: String,
+    pub morphs: Vec<Skill>,
+}
+
+/// Every skill in the table grouped under its base skill. Groups without a
+/// known base name are named after their morphs.
+pub fn skill_groups(master: &MasterTable) -> Vec<SkillGroup> {
+    let mut by_key: BTreeMap<String, Vec<Skill>> = BTreeMap::new();
+    for skill in &master.skills {
+        by_key.entry(skill_group_key(skill)).or_default().push(skill.clone());
+    }
+
+    by_key
+        .into_iter()
+        .map(|(key, mut morphs)| {
+            morphs.sort_by(|a, b| a.name.cmp(&b.name));
+            let name = match morphs.iter().find_map(|m| m.base_skill.clone()) {
+                Some(base) => base,
+                None => morphs
+                    .iter()
+                    .map(|m| m.display_name.clone().unwrap_or_else(|| m.name.clone()))
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+            };
+            SkillGroup { key, name, morphs }
+        })
+        .collect()
+}
+
+/// A skill group's value and the value of each of its morphs.
+pub type GroupedUsage<T> = (SkillGroup, T, Vec<(Skill, T)>);
+
+fn other_skill_group() -> SkillGroup {
+    SkillGroup { key: "Other".to_string(), name: "Other".to_string(), morphs: Vec::new() }
+}
+
+/// Folds per-skill counts into their groups, biggest group first, keeping
+/// the count of each morph. Groups past `n` are folded into "Other".
+fn group_skill_counts(master: &MasterTable, counts: Vec<(Skill, u32)>, n: usize) -> Vec<GroupedUsage<u32>> {
+    let mut grouped: Vec<GroupedUsage<u32>> = skill_groups(master)
+        .into_iter()
+        .map(|group| {
+            let mut morphs: Vec<(Skill, u32)> = counts
+                .iter()
+                .filter(|(skill, _)| group.morphs.iter().any(|m| m.id == skill.id))
+                .cloned()
+                .collect();
+            morphs.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            let total = morphs.iter().map(|(_, count)| count).sum();
+            (group, total, morphs)
+        })
+        .filter(|(_, total, _)| *total > 0)
+        .collect();
+
+    grouped.sort_by_key(|(_, total, _)| std::cmp::Reverse(*total));
+
+    if grouped.len() > n {
+        let other: u32 = grouped.drain(n..).map(|(_, total, _)| total).sum();
+        grouped.push((other_skill_group(), other, Vec::new()));
+    }
+
+    grouped
+}
+
+/// `top_n_skills_for_partitions` with morphs counted under their base skill.
+pub fn top_n_skills_for_partitions_grouped(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<GroupedUsage<u32>> {
+    group_skill_counts(master, top_n_skills_for_partitions(master, filter, usize::MAX), n)
+}
+
+/// `top_n_skills_for_partitions_normalised` with morphs counted under their
+/// base skill.
+pub fn top_n_skills_for_partitions_normalised_grouped(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<GroupedUsage<u32>> {
+    group_skill_counts(master, top_n_skills_for_partitions_normalised(master, filter, usize::MAX), n)
+}
+
+/// `percent_players_with_skills` with morphs counted under their base skill.
+/// A parse slotting both morphs counts once for the group.
+pub fn percent_players_with_skills_grouped(master: &MasterTable, filter: &RowFilter) -> Vec<GroupedUsage<f64>> {
+    let groups = skill_groups(master);
+    let group_of: HashMap<u16, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(i, group)| group.morphs.iter().map(move |m| (m.id, i)))
+        .collect();
+
+    let mut freq: HashMap<usize, u32> = HashMap::new();
+    let mut players_with_any_skill: u32 = 0;
+
+    for row in filter.rows(master) {
+        let unique_groups: HashSet<usize> = row.skill_ids().filter_map(|id| group_of.get(&id).copied()).collect();
+
+        if unique_groups.is_empty() {
+            continue;
+        }
+
+        players_with_any_skill = players_with_any_skill.saturating_add(1);
+
+        for group in unique_groups {
+            *freq.entry(group).or_insert(0) += 1;
+        }
+    }
+
+    if players_with_any_skill == 0 {
+        return Vec::new();
+    }
+
+    let per_skill = percent_players_with_skills(master, filter);
+
+    let mut results: Vec<GroupedUsage<f64>> = freq
+        .into_iter()
+        .map(|(i, count)| {
+            let group = groups[i].clone();
+            let pct = (count as f64) * 100.0 / (players_with_any_skill as f64);
+            let morphs = per_skill
+                .iter()
+                .filter(|(skill, _)| group.morphs.iter().any(|m| m.id == skill.id))
+                .cloned()
+                .collect();
+            (group, pct, morphs)
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.key.cmp(&b.0.key)));
+
+    results
+}
+
+/// How often a skill was slotted on each bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BarUsage {
+    pub front: u32,
+    pub back: u32,
+}
+
+impl BarUsage {
+    pub fn total(&self) -> u32 {
+        self.front + self.back
+    }
+
+    /// 1 for the front bar, 2 for the back bar, front on a tie
+    pub fn usual_bar(&self) -> u8 {
+        if self.front >= self.back { 1 } else { 2 }
+    }
+
+    pub fn front_percent(&self) -> f64 {
+        if self.total() == 0 {
+            return 0.0;
+        }
+        self.front as f64 * 100.0 / self.total() as f64
+    }
+}
+
+pub(crate) fn skill_or_unknown(master: &MasterTable, id: u16) -> Skill {
+    master.skill(id).cloned().unwrap_or_else(|| Skill {
+        id,
+        name: format!("Unknown ({})", id),
+        class: None,
+        tree: None,
+        display_name: None,
+        base_skill: None,
+    })
+}
+
+/// How often a skill is slotted alongside the one looked up, see
+/// `skill_companions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillCompanion {
+    pub skill: Skill,
+    /// rows slotting both
+    pub count: u32,
+    /// percent of the rows slotting the looked up skill that also slot this one
+    pub with_percent: f64,
+    /// percent of all rows that slot this one
+    pub baseline_percent: f64,
+    /// `with_percent` over `baseline_percent`
+    pub lift: f64,
+}
+
+/// Skills slotted alongside `skill_id` in the filter, highest lift first.
+/// Companions seen on fewer than `min_rows` rows are left out, since lift is
+/// noisy for rarely slotted skills. Rows with no skills are left out.
+pub fn skill_companions(master: &MasterTable, filter: &RowFilter, skill_id: u16, min_rows: u32) -> Vec<SkillCompanion> {
+    let mut rows: u32 = 0;
+    let mut rows_with_skill: u32 = 0;
+    let mut slotted: HashMap<u16, u32> = HashMap::new();
+    let mut together: HashMap<u16, u32> = HashMap::new();
+
+    for row in filter.rows(master) {
+        let ids: HashSet<u16> = row.skill_ids().collect();
+        if ids.is_empty() {
+            continue;
+        }
+        rows += 1;
+
+        let has_skill = ids.contains(&skill_id);
+        if has_skill {
+            rows_with_skill += 1;
+        }
+        for &id in &ids {
+            *slotted.entry(id).or_insert(0) += 1;
+            if has_skill && id != skill_id {
+                *together.entry(id).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut results: Vec<SkillCompanion> = together
+        .into_iter()
+        .filter(|(_, count)| *count >= min_rows.max(1))
+        .map(|(id, count)| {
+            let with_percent = count as f64 * 100.0 / rows_with_skill as f64;
+            let baseline_percent = slotted[&id] as f64 * 100.0 / rows as f64;
+            SkillCompanion {
+                skill: skill_or_unknown(master, id),
+                count,
+                with_percent,
+                baseline_percent,
+                lift: with_percent / baseline_percent,
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.lift.total_cmp(&a.lift).then(b.count.cmp(&a.count)).then(a.skill.id.cmp(&b.skill.id)));
+    results
+}
+
+/// Bar counts for every skill slotted in the partitions, most slotted first.
+pub fn skill_bar_usage(master: &MasterTable, filter: &RowFilter) -> Vec<(Skill, BarUsage)> {
+    let mut usage: HashMap<u16, BarUsage> = HashMap::new();
+
+    for row in filter.rows(master) {
+        for slotted in &row.skills {
+            let entry = usage.entry(slotted.skill_id).or_default();
+            match slotted.bar() {
+                1 => entry.front += 1,
+                _ => entry.back += 1,
+            }
+        }
+    }
+
+    let mut results: Vec<(Skill, BarUsage)> = usage
+        .into_iter()
+        .map(|(id, usage)| (skill_or_unknown(master, id), usage))
+        .collect();
+    results.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.id.cmp(&b.0.id)));
+    results
+}
+
+/// Most common pairs of ultimates, as a percentage of the rows with an
+/// ultimate on both bars. Which bar each one sits on doesn't matter.
+pub fn top_n_ultimate_pairs(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<((Skill, Skill), f64)> {
+    let pairs = filter
+        .rows(master)
+        .filter_map(|r| {
+            let (first, second) = (r.ultimate(1)?, r.ultimate(2)?);
+            Some((first.min(second), first.max(second)))
+        });
+
+    shares(pairs)
+        .into_iter()
+        .take(n)
+        .map(|((a, b), pct)| ((skill_or_unknown(master, a), skill_or_unknown(master, b)), pct))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UsageTarget {
+    Set(u16),
+    Skill(u16),
+}
+
+/// Percent of parses using each target per week. Weeks start on the first
+/// day of `filter.dates`, or on the Monday on or before the first parse when
+/// the range has no start, so they don't move as other filters change. Like
+/// `percent_players_with_sets` and `percent_players_with_skills`, a set is
+/// counted out of the rows wearing any known set and a skill out of the rows
+/// slotting any skill. Returns the first day of every week and one series
+/// per target, lined up with the weeks. Rows without a timestamp are left out.
+pub fn weekly_usage(
+    master: &MasterTable,
+    filter: &RowFilter,
+    targets: &[UsageTarget],
+) -> (Vec<Date>, Vec<Vec<f64>>) {
+    let canonical_id = canonical_set_ids(master);
+
+    let rows: Vec<(i64, &TableRow)> = filter
+        .rows(master)
+        .filter_map(|row| row.timestamp.map(|ms| (Date::from_timestamp(ms).days(), row)))
+        .collect();
+
+    let Some(first_parse) = rows.iter().map(|(day, _)| *day).min() else {
+        return (Vec::new(), vec![Vec::new(); targets.len()]);
+    };
+    let first_day = match filter.dates.start {
+        Some(start) => start.days(),
+        None => Date::from_days(first_parse).week_start().days(),
+    };
+    let last_day = rows.iter().map(|(day, _)| *day).max().unwrap_or(first_day);
+    let weeks = ((last_day - first_day) / 7 + 1) as usize;
+
+    // rows with any known set, rows with any skill
+    let mut totals: Vec<(u32, u32)> = vec![(0, 0); weeks];
+    let mut counts: Vec<Vec<u32>> = vec![vec![0; weeks]; targets.len()];
+
+    for (day, row) in rows {
+        let week = ((day - first_day) / 7) as usize;
+        if row.set_ids().any(|s| s != 0) {
+            totals[week].0 += 1;
+        }
+        if !row.skills.is_empty() {
+            totals[week].1 += 1;
+        }
+
+        for (i, target) in targets.iter().enumerate() {
+            let used = match *target {
+                UsageTarget::Set(id) => {
+                    let wanted = canonical_id.get(&id).copied().unwrap_or(id);
+                    row.set_ids()
+                        .any(|s| s != 0 && canonical_id.get(&s).copied().unwrap_or(s) == wanted)
+                }
+                UsageTarget::Skill(id) => row.skill_ids().any(|s| s == id),
+            };
+            if used {
+                counts[i][week] += 1;
+            }
+        }
+    }
+
+    let week_starts: Vec<Date> = (0..weeks)
+        .map(|w| Date::from_days(first_day + w as i64 * 7))
+        .collect();
+
+    let series: Vec<Vec<f64>> = targets
+        .iter()
+        .zip(counts)
+        .map(|(target, target_counts)| {
+            target_counts
+                .iter()
+                .zip(&totals)
+                .map(|(&count, &(with_sets, with_skills))| {
+                    let total = match target {
+                        UsageTarget::Set(_) => with_sets,
+                        UsageTarget::Skill(_) => with_skills,
+                    };
+                    if total == 0 { 0.0 } else { count as f64 * 100.0 / total as f64 }
+                })
+                .collect()
+        })
+        .collect();
+
+    (week_starts, series)
+}
+
+/// Percent of parses using each target in every partition of the filter
+/// (every partition when empty), the same as `percent_players_with_sets` and
+/// `percent_players_with_skills` give for that partition. Returns the
+/// partitions in order and one series per target, lined up with them.
+pub fn partition_usage(master: &MasterTable, filter: &RowFilter, targets: &[UsageTarget]) -> (Vec<u8>, Vec<Vec<f64>>) {
+    let canonical_id = canonical_set_ids(master);
+    let partitions: Vec<u8> = rows_by_partition(master, filter).into_keys().collect();
+
+    let mut series: Vec<Vec<f64>> = vec![Vec::with_capacity(partitions.len()); targets.len()];
+    for &partition in &partitions {
+        let partition_filter = filter.with_partitions(&[partition]);
+        let sets = if targets.iter().any(|t| matches!(t, UsageTarget::Set(_))) {
+            percent_players_with_sets(master, &partition_filter)
+        } else {
+            Vec::new()
+        };
+        let skills = if targets.iter().any(|t| matches!(t, UsageTarget::Skill(_))) {
+            percent_players_with_skills(master, &partition_filter)
+        } else {
+            Vec::new()
+        };
+
+        for (i, target) in targets.iter().enumerate() {
+            let pct = match *target {
+                UsageTarget::Set(id) => {
+                    let wanted = canonical_id.get(&id).copied().unwrap_or(id);
+                    sets.iter().find(|(s, _)| s.id == wanted).map(|(_, pct)| *pct)
+                }
+                UsageTarget::Skill(id) => skills.iter().find(|(s, _)| s.id == id).map(|(_, pct)| *pct),
+            };
+            series[i].push(pct.unwrap_or(0.0));
+        }
+    }
+
+    (partitions, series)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::dates::DateRange;
+
+    const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+    fn timestamp(year: i32, month: u8, day: u8) -> u64 {
+        Date { year, month, day }.days() as u64 * MS_PER_DAY
+    }
+
+    fn skill(id: u16, name: &str, class: Option<&str>) -> Skill {
+        Skill {
+            id,
+            name: format!("ability_{}.png", id),
+            class: class.map(str::to_string),
+            tree: None,
+            display_name: Some(name.to_string()),
+            base_skill: None,
+        }
+    }
+
+    pub(crate) fn row(partition_id: u8, ranking: u8, dps: u32, skills: &[u16], sets: &[u16]) -> TableRow {
+        TableRow {
+            zone_id: 17,
+            boss_id: 55,
+            partition_id,
+            ranking,
+            player_id: 1,
+            character_id: 0,
+            dps,
+            boss: true,
+            timestamp: None,
+            skills: skills.iter().enumerate().map(|(slot, &skill_id)| SkillSlot { skill_id, slot: slot as u8 }).collect(),
+            gear: sets
+                .iter()
+                .enumerate()
+                .map(|(slot, &set_id)| GearPiece { slot: slot as u8, set_id, item_id: 0, icon_id: 0 })
+                .collect(),
+            front_bar: WeaponType::Unknown,
+            back_bar: WeaponType::Unknown,
+            armour_weights: ArmourWeights::default(),
+            classes: Vec::new(),
+        }
+    }
+
+    /// Skills 1 to 4 and sets 1 to 3, with set 2 the Perfected version of 1.
+    pub(crate) fn table(rows: Vec<TableRow>) -> MasterTable {
+        let skills = vec![
+            skill(1, "Glyphic of the Tides", Some("Arcanist")),
+            skill(2, "Crescent Sweep", Some("Templar")),
+            skill(3, "Barbed Trap", None),
+            skill(4, "Camouflaged Hunter", Some("Nightblade")),
+        ];
+        let mut master = MasterTable {
+            rows,
+            players: vec![Player { id: 1, name: "@PlayerOne".to_string(), text: String::new() }],
+            characters: Vec::new(),
+            skills,
+            sets: vec![
+                ItemSet { id: 1, name: "Slivers".to_string() },
+                ItemSet { id: 2, name: "Perfected Slivers".to_string() },
+                ItemSet { id: 3, name: "Relequen".to_string() },
+            ],
+            icons: Vec::new(),
+            index: Default::default(),
+        };
+        master.infer_row_classes();
+        master
+    }
+
+    #[test]
+    fn set_for_item_name_matches_whole_words() {
+        let sets = vec![
+            ItemSet { id: 1, name: "Order".to_string() },
+            ItemSet { id: 2, name: "Pale Order".to_string() },
+            ItemSet { id: 3, name: "Perfected Pale Order".to_string() },
+            ItemSet { id: 4, name: "Slivers".to_string() },
+        ];
+
+        assert_eq!(set_for_item_name("Ring of the Pale Order", &sets), Some(2));
+        assert_eq!(set_for_item_name("Perfected Ring of the Pale Order", &sets), Some(3));
+        assert_eq!(set_for_item_name("Order's Boots", &sets), Some(1));
+        assert_eq!(set_for_item_name("Border Guard Helm", &sets), None);
+        assert_eq!(set_for_item_name("Sliversmith Gloves", &sets), None);
+    }
+
+    #[test]
+    fn unmapped_gear_is_reported_but_not_attributed() {
+        let sets = vec![ItemSet { id: 4, name: "Slivers".to_string() }];
+        let line = r#"15,55,27,row-1,Char One,@PlayerOne,150000,,"['ability_a.png']","[{'name': 'Slivers Ring', 'icon': 'r.png', 'id': 999001}, {'name': 'Slivers Mask', 'icon': 'm.png', 'id': 777}]""#;
+        let entry = parse_csv_line(line).unwrap();
+
+        let set_ids: HashMap<u32, u16> = HashMap::from([(777, 4)]);
+        let meta = HashMap::new();
+        let mut builder = TableBuilder::new(table(Vec::new()), &meta, &set_ids, &sets);
+        let mut report = IngestReport::new(IngestMode::Strict);
+        builder.process_entry(&entry, true, Path::new("boss.csv"), &mut report).unwrap();
+
+        let gear: Vec<u16> = builder.master.rows[0].set_ids().collect();
+        assert_eq!(gear, vec![0, 4]);
+        assert_eq!(builder.unmapped_gear.len(), 1);
+        assert_eq!(builder.unmapped_gear[&999001].suggested_set, Some(4));
+    }
+
+    #[test]
+    fn parse_partition_csv_line_reads_scraper_columns() {
+        // as written by `save_results_to_csv` in data/streamlined.ipynb
+        let line = "row-3,Char One,@PlayerOne,150000.5,1700000000000,\
+            https://assets.rpglogs.com/img/eso/abilities/ability_a.png;https://assets.rpglogs.com/img/eso/abilities/ability_b.png,\
+            101:gear_breton_ring_a.png:Slivers Ring;202:gear_breton_medium_feet_d.png:Boots of the Order: Reforged";
+        let row = parse_partition_csv_line(line, 17, 55, 27).unwrap();
+
+        assert_eq!((row.zone, row.boss, row.partition), (17, 55, 27));
+        assert_eq!(row.row_id, "row-3");
+        assert_eq!(row.at_name, "@PlayerOne");
+        assert_eq!(row.timestamp, Some(1700000000000));
+        assert_eq!(
+            row.talents,
+            vec![
+                "https://assets.rpglogs.com/img/eso/abilities/ability_a.png",
+                "https://assets.rpglogs.com/img/eso/abilities/ability_b.png",
+            ]
+        );
+
+        let gear: Vec<(u32, &str, &str, u8)> = row.gear.iter().map(|g| (g.id, g.name.as_str(), g.icon.as_str(), g.slot)).collect();
+        assert_eq!(
+            gear,
+            vec![
+                (101, "Slivers Ring", "gear_breton_ring_a.png", 0),
+                (202, "Boots of the Order: Reforged", "gear_breton_medium_feet_d.png", 1),
+            ]
+        );
+
+        // older scrapes wrote `id:name`
+        let old = parse_partition_csv_line("row-3,Char One,@PlayerOne,1,,,101:Slivers Ring", 17, 55, 27).unwrap();
+        assert_eq!((old.gear[0].name.as_str(), old.gear[0].icon.as_str()), ("Slivers Ring", ""));
+
+        // an empty slot keeps the pieces after it in place
+        let gap = parse_partition_csv_line("row-3,Char One,@PlayerOne,1,,,101:A;;202:B", 17, 55, 27).unwrap();
+        let slots: Vec<(u32, u8)> = gap.gear.iter().map(|g| (g.id, g.slot)).collect();
+        assert_eq!(slots, vec![(101, 0), (202, 2)]);
+
+        assert_eq!(
+            parse_partition_csv_line("row-3,Char One,,1,,,", 17, 55, 27).unwrap_err(),
+            FieldError::Missing { field: "@name" }
+        );
+        assert_eq!(
+            parse_partition_csv_line("row-3,Char One,@PlayerOne,1,,,x:Ring", 17, 55, 27).unwrap_err(),
+            FieldError::Invalid { field: "gear", value: "x:Ring".to_string() }
+        );
+        assert_eq!(
+            parse_partition_csv_line("row-3,Char One,@PlayerOne,1,,", 17, 55, 27).unwrap_err(),
+            FieldError::MissingFields { expected: 7, found: 6 }
+        );
+    }
+
+    #[test]
+    fn partition_rows_without_icons_are_counted() {
+        let text = "row_id,name,at_name,dps,timestamp,talents,gear\n\
+            row-1,Char One,@PlayerOne,1,,,101:Slivers Ring\n\
+            row-2,Char Two,@PlayerTwo,1,,,101:gear_breton_ring_a.png:Slivers Ring\n\
+            row-3,Char Three,@PlayerThree,1,,,\n";
+        let mut report = IngestReport::new(IngestMode::Strict);
+        let rows = parse_partition_csv_text(text, Path::new("partition_27.csv"), (17, 55, 27), &mut report).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(report.rows_read, 3);
+        assert_eq!(report.rows_without_icons, 1);
+    }
+
+    #[test]
+    fn duplicate_row_keys_are_refused() {
+        let line = r#"17,55,27,row-1,Char One,@PlayerOne,150000,,"['ability_a.png']","[]""#;
+        let entry = parse_csv_line(line).unwrap();
+        let meta = HashMap::new();
+        let set_ids = HashMap::new();
+
+        let mut builder = TableBuilder::new(table(Vec::new()), &meta, &set_ids, &[]);
+        let mut report = IngestReport::new(IngestMode::Strict);
+        builder.process_entry(&entry, true, Path::new("boss.csv"), &mut report).unwrap();
+        // the same ranking on the other dps type is a different row
+        builder.process_entry(&entry, false, Path::new("total.csv"), &mut report).unwrap();
+
+        let error = builder.process_entry(&entry, true, Path::new("boss.csv"), &mut report).unwrap_err();
+        let key = RowKey { boss_id: 55, partition_id: 27, ranking: 1, boss: true };
+        assert_eq!(error, IngestError::Duplicate { file: PathBuf::from("boss.csv"), key });
+
+        // merging into a table that already has the row refuses it too
+        let master = builder.master;
+        let mut builder = TableBuilder::new(master, &meta, &set_ids, &[]);
+        let mut report = IngestReport::new(IngestMode::Lenient);
+        builder.process_entry(&entry, true, Path::new("boss.csv"), &mut report).unwrap();
+
+        assert_eq!(builder.master.rows.len(), 2);
+        assert_eq!(report.rows_skipped, 1);
+        assert_eq!(report.errors, vec![IngestError::Duplicate { file: PathBuf::from("boss.csv"), key }]);
+    }
+
+    #[test]
+    fn weapon_pieces_match_whole_icon_parts() {
+        let url = |name: &str| format!("https://assets.rpglogs.com/img/eso/abilities/{}.png", name);
+
+        assert_eq!(weapon_piece_from_icon(&url("gear_breton_1hsword_d")), Some(WeaponPiece::OneHanded));
+        assert_eq!(weapon_piece_from_icon("gear_ancient_elf_dagger_a"), Some(WeaponPiece::OneHanded));
+        assert_eq!(weapon_piece_from_icon(&url("gear_nord_2hhammer_d")), Some(WeaponPiece::TwoHanded));
+        assert_eq!(weapon_piece_from_icon(&url("gear_altmer_bow_d")), Some(WeaponPiece::Bow));
+        assert_eq!(weapon_piece_from_icon("gear_breton_shield_d.dds"), Some(WeaponPiece::Shield));
+        assert_eq!(weapon_piece_from_icon(&url("gear_altmer_staff_d")), Some(WeaponPiece::DestructionStaff));
+        assert_eq!(weapon_piece_from_icon(&url("gear_altmer_healing_staff_d")), Some(WeaponPiece::RestorationStaff));
+        assert_eq!(weapon_piece_from_icon("Gear_Altmer_RestoStaff_D"), Some(WeaponPiece::RestorationStaff));
+
+        assert_eq!(weapon_piece_from_icon(&url("gear_breton_heavy_elbow_d")), None);
+        assert_eq!(weapon_piece_from_icon(&url("gear_rainbow_medium_chest_a")), None);
+        assert_eq!(weapon_piece_from_icon(&url("gear_breton_light_shoulders_d")), None);
+        assert_eq!(weapon_piece_from_icon(&url("gear_breton_ring_a")), None);
+        assert_eq!(weapon_piece_from_icon(""), None);
+
+        assert_eq!(weapon_type_from_icons(Some("gear_breton_1haxe_d"), Some("gear_breton_dagger_d")), WeaponType::DualWield);
+        assert_eq!(weapon_type_from_icons(Some("gear_breton_1hmace_d"), Some("gear_breton_shield_d")), WeaponType::OneHandAndShield);
+        assert_eq!(weapon_type_from_icons(Some("gear_breton_2hsword_d"), None), WeaponType::TwoHanded);
+        assert_eq!(weapon_type_from_icons(Some("gear_elbow_d"), None), WeaponType::Unknown);
+    }
+
+    #[test]
+    fn ultimate_pairs_use_the_ultimate_slots() {
+        let slots = |skills: &[(u8, u16)]| skills.iter().map(|&(slot, skill_id)| SkillSlot { skill_id, slot }).collect();
+
+        // slot 2 left empty, the ultimates still sit in slots 5 and 11
+        let mut with_gap = row(27, 1, 100, &[], &[]);
+        with_gap.skills = slots(&[(0, 1), (1, 2), (3, 1), (4, 2), (5, 3), (6, 1), (11, 4)]);
+        let mut reversed = row(27, 2, 100, &[], &[]);
+        reversed.skills = slots(&[(0, 1), (5, 4), (6, 2), (11, 3)]);
+        // no back bar ultimate, and a stray skill past the back bar isn't one
+        let mut no_back_ultimate = row(27, 3, 100, &[], &[]);
+        no_back_ultimate.skills = slots(&[(0, 1), (5, 3), (6, 2), (10, 4), (17, 4)]);
+
+        let master = table(vec![with_gap, reversed, no_back_ultimate]);
+        assert_eq!(master.rows[0].ultimate(1), Some(3));
+        assert_eq!(master.rows[0].ultimate(2), Some(4));
+        assert_eq!(master.rows[2].ultimate(2), None);
+
+        let pairs = top_n_ultimate_pairs(&master, &RowFilter::default(), 10);
+        assert_eq!(pairs.len(), 1);
+        let ((first, second), pct) = &pairs[0];
+        assert_eq!((first.id, second.id), (3, 4));
+        assert!(close(*pct, 100.0));
+    }
+
+    #[test]
+    fn set_co_occurrence_counts_conditional_shares_and_lift() {
+        let mut master = table(vec![
+            row(27, 1, 100, &[1], &[1, 3]),
+            // Perfected Slivers counts as Slivers
+            row(27, 2, 100, &[1], &[2, 3, 4, 3]),
+            row(27, 3, 100, &[1], &[3, 4]),
+            row(27, 4, 100, &[1], &[4]),
+            // no known set, left out of every denominator
+            row(27, 5, 100, &[1], &[0]),
+        ]);
+        master.sets.push(ItemSet { id: 4, name: "Pillager's Profit".to_string() });
+
+        // 4 rows; Slivers on 2, Relequen on 3, Pillager's on 3
+        let pairs: Vec<(u16, u16, u32, f64, f64)> = set_co_occurrence(&master, &RowFilter::default())
+            .into_iter()
+            .map(|p| (p.first.id, p.second.id, p.count, p.second_given_first, p.lift))
+            .collect();
+        let expected = [
+            (1, 3, 2, 100.0, 2.0 * 4.0 / (2.0 * 3.0)),
+            (3, 1, 2, 200.0 / 3.0, 2.0 * 4.0 / (3.0 * 2.0)),
+            (3, 4, 2, 200.0 / 3.0, 2.0 * 4.0 / (3.0 * 3.0)),
+            (4, 3, 2, 200.0 / 3.0, 2.0 * 4.0 / (3.0 * 3.0)),
+            (1, 4, 1, 50.0, 4.0 / (2.0 * 3.0)),
+            (4, 1, 1, 100.0 / 3.0, 4.0 / (3.0 * 2.0)),
+        ];
+
+        assert_eq!(pairs.len(), expected.len(), "{:?}", pairs);
+        for (pair, want) in pairs.iter().zip(expected) {
+            assert_eq!((pair.0, pair.1, pair.2), (want.0, want.1, want.2), "{:?}", pairs);
+            assert!(close(pair.3, want.3) && close(pair.4, want.4), "{:?} != {:?}", pair, want);
+        }
+    }
+
+    #[test]
+    fn skill_companions_rank_by_lift() {
+        let master = table(vec![
+            // skill 1 on both bars still counts the row once
+            row(27, 1, 100, &[1, 2, 3, 1], &[]),
+            row(27, 2, 100, &[1, 2], &[]),
+            row(27, 3, 100, &[2, 3], &[]),
+            row(27, 4, 100, &[3, 4], &[]),
+            // no skills, left out of every denominator
+            row(27, 5, 100, &[], &[]),
+        ]);
+        let filter = RowFilter::default();
+
+        // 4 rows, 2 with skill 1; skill 2 is on 3 rows and skill 3 on 3
+        let companions: Vec<(u16, u32, f64, f64, f64)> = skill_companions(&master, &filter, 1, 1)
+            .into_iter()
+            .map(|c| (c.skill.id, c.count, c.with_percent, c.baseline_percent, c.lift))
+            .collect();
+        let expected = [(2, 2, 100.0, 75.0, 100.0 / 75.0), (3, 1, 50.0, 75.0, 50.0 / 75.0)];
+
+        assert_eq!(companions.len(), expected.len(), "{:?}", companions);
+        for (companion, want) in companions.iter().zip(expected) {
+            assert_eq!((companion.0, companion.1), (want.0, want.1), "{:?}", companions);
+            assert!(
+                close(companion.2, want.2) && close(companion.3, want.3) && close(companion.4, want.4),
+                "{:?} != {:?}",
+                companion,
+                want
+            );
+        }
+
+        let ids = |min_rows| skill_companions(&master, &filter, 1, min_rows).into_iter().map(|c| c.skill.id).collect::<Vec<_>>();
+        assert_eq!(ids(2), vec![2]);
+        assert_eq!(ids(3), Vec::<u16>::new());
+        assert!(skill_companions(&master, &filter, 99, 1).is_empty());
+    }
+
+    pub(crate) fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn weekly_usage_starts_weeks_on_monday_and_counts_like_the_shares() {
+        let wednesday = timestamp(2024, 4, 3);
+        let next_tuesday = timestamp(2024, 4, 9);
+        let rows = vec![
+            TableRow { timestamp: Some(wednesday), ..row(27, 1, 100, &[1], &[1]) },
+            TableRow { timestamp: Some(wednesday), ..row(27, 2, 100, &[1], &[]) },
+            TableRow { timestamp: Some(next_tuesday), ..row(27, 3, 100, &[2], &[3]) },
+        ];
+        let master = table(rows);
+
+        let targets = [UsageTarget::Set(2), UsageTarget::Skill(1)];
+        let (weeks, series) = weekly_usage(&master, &RowFilter::default(), &targets);
+
+        assert_eq!(weeks, vec![Date { year: 2024, month: 4, day: 1 }, Date { year: 2024, month: 4, day: 8 }]);
+        // the row without sets doesn't count against Slivers
+        assert_eq!(series[0], vec![100.0, 0.0]);
+        assert_eq!(series[1], vec![100.0, 0.0]);
+
+        let share = percent_players_with_sets(&master, &RowFilter::default().with_partitions(&[27]));
+        assert!(close(share[0].1, 50.0));
+
+        // a narrower filter keeps the same week boundaries
+        let filter = RowFilter { rankings: Some((2, 3)), ..RowFilter::default() };
+        let (weeks, _) = weekly_usage(&master, &filter, &targets);
+        assert_eq!(weeks[0], Date { year: 2024, month: 4, day: 1 });
+
+        let from_thursday = RowFilter {
+            dates: DateRange { start: Some(Date { year: 2024, month: 4, day: 4 }), end: None },
+            ..RowFilter::default()
+        };
+        let (weeks, _) = weekly_usage(&master, &from_thursday, &targets);
+        assert_eq!(weeks, vec![Date { year: 2024, month: 4, day: 4 }]);
+    }
+
+    #[test]
+    fn grouped_skill_shares_count_each_group_once_per_row() {
+        let rows = vec![
+            row(27, 1, 100, &[1, 2, 3], &[]),
+            row(27, 2, 100, &[1, 4], &[]),
+            row(27, 3, 100, &[3], &[]),
+            row(27, 4, 100, &[4], &[]),
+            row(27, 5, 100, &[2], &[]),
+            row(27, 6, 100, &[], &[]),
+        ];
+        let mut master = table(rows);
+        for skill in &mut master.skills[..2] {
+            skill.base_skill = Some("Tides".to_string());
+        }
+
+        let results = percent_players_with_skills_grouped(&master, &RowFilter::default());
+        let summary: Vec<_> = results.iter().map(|(group, pct, _)| (group.key.as_str(), *pct)).collect();
+
+        // out of the five rows with a skill; both morphs in one row count once,
+        // and the tied groups come out in key order
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].0, "Tides");
+        assert!(close(summary[0].1, 60.0));
+        assert_eq!(summary[1].0, "ability_3.png");
+        assert_eq!(summary[2].0, "ability_4.png");
+        assert!(close(summary[1].1, 40.0) && close(summary[2].1, 40.0));
+
+        let morphs: Vec<_> = results[0].2.iter().map(|(skill, pct)| (skill.id, *pct)).collect();
+        assert_eq!(morphs.len(), 2);
+        assert!(morphs.iter().all(|&(id, pct)| (id == 1 || id == 2) && close(pct, 40.0)));
+    }
+
+    #[test]
+    fn armour_weights_count_whole_icon_parts() {
+        let icons = [
+            "https://assets.rpglogs.com/img/eso/abilities/gear_breton_medium_chest_d.png",
+            "gear_breton_medium_legs_d.png",
+            "gear_redguard_medium_feet_a.png",
+            "gear_breton_medium_hands_d.png",
+            "gear_breton_medium_waist_d.png",
+            "gear_altmer_light_head_a.png",
+            "gear_altmer_light_shoulders_d.png",
+            "gear_nord_heavy_head_d.png",
+            // jewelry, weapons and unknown icons don't count
+            "gear_breton_ring_a.png",
+            "gear_breton_1hsword_d.png",
+            "gear_u30_lightweave_chest_a.png",
+            "gear_mediumrare_legs_a.png",
+            "",
+        ];
+        let weights = ArmourWeights::from_icons(icons.into_iter());
+        assert_eq!(weights, ArmourWeights { light: 2, medium: 5, heavy: 1 });
+        assert_eq!(weights.label(), "5 Medium / 2 Light / 1 Heavy");
+
+        let icons = ["gear_nord_heavy_chest_d.png"; 7];
+        assert_eq!(ArmourWeights::from_icons(icons.into_iter()), ArmourWeights { light: 0, medium: 0, heavy: 7 });
+    }
+
+    #[test]
+    fn armour_weight_shares_per_partition_and_class() {
+        let medium = ArmourWeights { light: 2, medium: 5, heavy: 0 };
+        let heavy = ArmourWeights { light: 0, medium: 0, heavy: 7 };
+        let rows = vec![
+            TableRow { armour_weights: medium, ..row(27, 1, 100, &[1], &[]) },
+            TableRow { armour_weights: heavy, ..row(27, 2, 100, &[1], &[]) },
+            TableRow { armour_weights: medium, ..row(28, 1, 100, &[2], &[]) },
+            // no class
+            TableRow { armour_weights: medium, ..row(28, 2, 100, &[3], &[]) },
+            // no known body pieces
+            row(28, 3, 100, &[2], &[]),
+        ];
+        let master = table(rows);
+        let filter = RowFilter::default();
+
+        let overall = percent_players_with_armour_weights(&master, &filter);
+        assert_eq!(overall.len(), 2);
+        assert_eq!(overall[0].0, medium);
+        assert!(close(overall[0].1, 75.0) && close(overall[1].1, 25.0));
+
+        // ties come out in `ArmourWeights` order
+        let per_partition = armour_weights_per_partition(&master, &filter);
+        assert_eq!(per_partition.iter().map(|(p, _)| *p).collect::<Vec<_>>(), vec![27, 28]);
+        assert_eq!(per_partition[0].1.iter().map(|(w, _)| *w).collect::<Vec<_>>(), vec![heavy, medium]);
+        assert!(per_partition[0].1.iter().all(|(_, pct)| close(*pct, 50.0)));
+        assert_eq!(per_partition[1].1.len(), 1);
+        assert!(close(per_partition[1].1[0].1, 100.0));
+
+        let per_class = armour_weights_per_class(&master, &filter);
+        assert_eq!(per_class.iter().map(|(c, _)| c.as_str()).collect::<Vec<_>>(), vec!["Arcanist", "Templar"]);
+        assert_eq!(per_class[0].1.iter().map(|(w, _)| *w).collect::<Vec<_>>(), vec![heavy, medium]);
+        assert_eq!(per_class[1].1.len(), 1);
+        assert_eq!(per_class[1].1[0].0, medium);
+        assert!(close(per_class[1].1[0].1, 100.0));
+    }
+}
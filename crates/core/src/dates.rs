@@ -0,0 +1,135 @@
+use std::{fmt, str::FromStr};
+
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// A UTC calendar day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Days since 1970-01-01 to a calendar date, from Howard Hinnant's
+    /// `civil_from_days`.
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /// Days since 1970-01-01, the inverse of `from_days`.
+    pub fn days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// The day a millisecond unix timestamp falls on.
+    pub fn from_timestamp(ms: u64) -> Date {
+        Date::from_days((ms / MS_PER_DAY) as i64)
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    /// The Monday on or before this day. 1970-01-01 was a Thursday.
+    pub fn week_start(&self) -> Date {
+        let days = self.days();
+        Date::from_days(days - (days + 3).rem_euclid(7))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parses `YYYY-MM-DD`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date '{}', expected YYYY-MM-DD", s);
+
+        let mut parts = s.trim().splitn(3, '-');
+        let year: i32 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let month: u8 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let day: u8 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+
+        let date = Date { year, month, day };
+        // rejects days past the end of the month, e.g. 2025-02-30
+        if Date::from_days(date.days()) != date {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+/// Inclusive range of days. An open end is unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DateRange {
+    pub start: Option<Date>,
+    pub end: Option<Date>,
+}
+
+impl DateRange {
+    pub const ALL: DateRange = DateRange { start: None, end: None };
+
+    pub fn is_all(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    /// Rows without a timestamp only match an unbounded range.
+    pub fn contains(&self, timestamp: Option<u64>) -> bool {
+        if self.is_all() {
+            return true;
+        }
+        let Some(ms) = timestamp else {
+            return false;
+        };
+        let date = Date::from_timestamp(ms);
+        self.start.is_none_or(|start| date >= start) && self.end.is_none_or(|end| date <= end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn week_start_is_the_monday_on_or_before() {
+        let monday = Date { year: 2024, month: 4, day: 1 };
+        for day in 1..=7 {
+            assert_eq!(Date { year: 2024, month: 4, day }.week_start(), monday);
+        }
+        assert_eq!(Date { year: 2024, month: 4, day: 8 }.week_start(), Date { year: 2024, month: 4, day: 8 });
+        assert_eq!(Date { year: 1970, month: 1, day: 1 }.week_start(), Date { year: 1969, month: 12, day: 29 });
+    }
+
+    #[test]
+    fn from_str_reads_calendar_days_only() {
+        assert_eq!("2024-04-01".parse(), Ok(Date { year: 2024, month: 4, day: 1 }));
+        assert_eq!(" 2024-02-29 ".parse(), Ok(Date { year: 2024, month: 2, day: 29 }));
+        for bad in ["2025-02-29", "2024-13-01", "2024-04-00", "2024-04", "2024/04/01", "2024-04-01x", ""] {
+            assert_eq!(bad.parse::<Date>(), Err(format!("invalid date '{}', expected YYYY-MM-DD", bad)), "{}", bad);
+        }
+
+        let date = Date { year: 2024, month: 4, day: 1 };
+        assert_eq!(date.to_string().parse(), Ok(date));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{data::{MasterTable, TableRow}, dates::DateRange};
+
+/// Which esologs ranking a row came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DpsType {
+    #[default]
+    Both,
+    /// `boss_dps.csv`, damage to the boss only
+    SingleTarget,
+    /// `total_dps.csv`, damage to everything
+    Aoe,
+}
+
+impl DpsType {
+    pub const ALL: [DpsType; 3] = [DpsType::Both, DpsType::SingleTarget, DpsType::Aoe];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DpsType::Both => "ST + AOE",
+            DpsType::SingleTarget => "ST",
+            DpsType::Aoe => "AOE",
+        }
+    }
+
+    pub fn matches(&self, boss: bool) -> bool {
+        match self {
+            DpsType::Both => true,
+            DpsType::SingleTarget => boss,
+            DpsType::Aoe => !boss,
+        }
+    }
+}
+
+/// Rows an analytics function counts. Empty lists and open ranges don't
+/// filter anything, so the default keeps every row.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RowFilter {
+    pub partitions: Vec<u8>,
+    pub bosses: Vec<u8>,
+    /// trials by zone id, see `zone_to_zone_name`
+    pub zones: Vec<u8>,
+    pub dps_type: DpsType,
+    /// inclusive, 1 is the top parse
+    pub rankings: Option<(u8, u8)>,
+    pub dates: DateRange,
+}
+
+impl RowFilter {
+    /// Every row in the given partitions, or every row when empty.
+    pub fn partitions(partitions: &[u8]) -> RowFilter {
+        RowFilter { partitions: partitions.to_vec(), ..Default::default() }
+    }
+
+    /// This filter over other partitions, for charts that pick their own
+    /// patches but share the rest.
+    pub fn with_partitions(&self, partitions: &[u8]) -> RowFilter {
+        RowFilter { partitions: partitions.to_vec(), ..self.clone() }
+    }
+
+    pub fn matches(&self, row: &TableRow) -> bool {
+        (self.partitions.is_empty() || self.partitions.contains(&row.partition_id))
+            && (self.bosses.is_empty() || self.bosses.contains(&row.boss_id))
+            && (self.zones.is_empty() || self.zones.contains(&row.zone_id))
+            && self.dps_type.matches(row.boss)
+            && self.rankings.is_none_or(|(best, worst)| (best..=worst).contains(&row.ranking))
+            && self.dates.contains(row.timestamp)
+    }
+
+    /// Matching rows in table order. Only the rows of the chosen partitions
+    /// or bosses are looked at when there are any.
+    pub fn rows<'a, 'm: 'a>(&'a self, master: &'m MasterTable) -> impl Iterator<Item = &'m TableRow> + 'a {
+        let positions = self.positions(master);
+        let every_row = positions.is_none().then(|| master.rows.iter()).into_iter().flatten();
+        let indexed = positions.into_iter().flatten().map(|i| &master.rows[i]);
+        every_row.chain(indexed).filter(move |row| self.matches(row))
+    }
+
+    /// Positions of the rows in the chosen partitions, or failing that the
+    /// chosen bosses, or `None` to scan every row.
+    fn positions(&self, master: &MasterTable) -> Option<Vec<usize>> {
+        let mut positions: Vec<usize> = if !self.partitions.is_empty() {
+            self.partitions.iter().flat_map(|&p| master.partition_positions(p)).copied().collect()
+        } else if !self.bosses.is_empty() {
+            self.bosses.iter().flat_map(|&b| master.boss_positions(b)).copied().collect()
+        } else {
+            return None;
+        };
+        positions.sort_unstable();
+        positions.dedup();
+        Some(positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RowKey;
+    use crate::data::tests::{row, table};
+
+    fn master() -> MasterTable {
+        table(vec![
+            row(27, 1, 100, &[1], &[]),
+            TableRow { boss_id: 56, ..row(28, 1, 100, &[1], &[]) },
+            row(29, 1, 100, &[1], &[]),
+            TableRow { boss_id: 56, ..row(27, 2, 100, &[1], &[]) },
+            row(28, 2, 100, &[1], &[]),
+            TableRow { boss_id: 56, boss: false, ..row(29, 2, 100, &[1], &[]) },
+        ])
+    }
+
+    /// The indexed rows against a scan of every row.
+    fn assert_same_rows(master: &MasterTable, filter: &RowFilter) -> Vec<RowKey> {
+        let indexed: Vec<RowKey> = filter.rows(master).map(TableRow::key).collect();
+        let scanned: Vec<RowKey> = master.rows.iter().filter(|r| filter.matches(r)).map(TableRow::key).collect();
+        assert_eq!(indexed, scanned);
+        indexed
+    }
+
+    #[test]
+    fn indexed_rows_match_a_full_scan() {
+        let master = master();
+
+        // several partitions, out of order and repeated, come back once each in table order
+        let rows = assert_same_rows(&master, &RowFilter::partitions(&[29, 27, 29]));
+        assert_eq!(rows.iter().map(|k| k.partition_id).collect::<Vec<_>>(), vec![27, 29, 27, 29]);
+
+        // bosses alone use the boss index
+        let bosses = RowFilter { bosses: vec![56], ..RowFilter::default() };
+        assert_eq!(assert_same_rows(&master, &bosses).len(), 3);
+
+        // partitions and bosses together
+        let both = RowFilter { bosses: vec![56], ..RowFilter::partitions(&[27, 28]) };
+        assert_eq!(assert_same_rows(&master, &both).len(), 2);
+
+        let aoe = RowFilter { dps_type: DpsType::Aoe, ..bosses };
+        assert_eq!(assert_same_rows(&master, &aoe).len(), 1);
+        assert!(assert_same_rows(&master, &RowFilter::partitions(&[30])).is_empty());
+        assert_eq!(assert_same_rows(&master, &RowFilter::default()).len(), 6);
+    }
+}
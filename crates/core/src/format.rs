@@ -0,0 +1,988 @@
+use std::fmt;
+
+use crate::data::{self, BACK_BAR_SLOTS, BODY_SLOTS, FRONT_BAR_SLOTS, MasterTable, TableRow, boss_to_zone, weapon_type_from_icons};
+
+/// File layout of `master_table.bin`:
+///
+/// | bytes | contents                                   |
+/// |-------|--------------------------------------------|
+/// | 0..4  | `MAGIC`                                    |
+/// | 4..6  | schema version, little endian u16          |
+/// | 6..10 | FNV-1a checksum of the payload, little endian u32 |
+/// | 10..  | bitcode encoded `MasterTable` for that version |
+///
+/// Files written before the header existed are bare bitcode of the version 1
+/// schema and still load.
+pub const MAGIC: [u8; 4] = *b"ESMT";
+pub const HEADER_LEN: usize = 10;
+
+/// Bump this whenever `MasterTable` or anything inside it changes shape, and
+/// add a match arm to `decode` that migrates the old layout. The old layouts
+/// live in the `v*` modules below and never use the types in `data`, so
+/// changing those can't change how old files decode.
+pub const CURRENT_VERSION: u16 = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    UnsupportedVersion(u16),
+    ChecksumMismatch { expected: u32, found: u32 },
+    Decode { version: u16, message: String },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported master table version {} (this build reads up to {})",
+                v, CURRENT_VERSION
+            ),
+            FormatError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "master table checksum mismatch (header {:08x}, payload {:08x}), the file is corrupt",
+                expected, found
+            ),
+            FormatError::Decode { version, message } => {
+                write!(f, "failed to decode version {} master table: {}", version, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+fn checksum(payload: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for &byte in payload {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+/// Splits a file into its schema version and payload, checking the checksum.
+/// Headerless files are reported as version 1.
+pub fn read_header(bytes: &[u8]) -> Result<(u16, &[u8]), FormatError> {
+    if bytes.len() < HEADER_LEN || bytes[0..4] != MAGIC {
+        return Ok((1, bytes));
+    }
+
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    let expected = u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]);
+    let payload = &bytes[HEADER_LEN..];
+
+    let found = checksum(payload);
+    if found != expected {
+        return Err(FormatError::ChecksumMismatch { expected, found });
+    }
+
+    Ok((version, payload))
+}
+
+pub fn encode(master: &MasterTable) -> Vec<u8> {
+    with_header(CURRENT_VERSION, &bitcode::encode(master))
+}
+
+fn with_header(version: u16, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes.extend_from_slice(&checksum(payload).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// Decodes a file of any supported version, migrating it to the current schema.
+pub fn decode(bytes: &[u8]) -> Result<MasterTable, FormatError> {
+    let (version, payload) = read_header(bytes)?;
+
+    let decode_error = |e: bitcode::Error| FormatError::Decode { version, message: e.to_string() };
+
+    match version {
+        CURRENT_VERSION => bitcode::decode::<MasterTable>(payload).map_err(decode_error),
+        7 => bitcode::decode::<v7::MasterTable>(payload).map(MasterTable::from).map_err(decode_error),
+        6 => bitcode::decode::<v6::MasterTable>(payload)
+            .map(v7::MasterTable::from)
+            .map(MasterTable::from)
+            .map_err(decode_error),
+        5 => bitcode::decode::<v5::MasterTable>(payload)
+            .map(v6::MasterTable::from)
+            .map(v7::MasterTable::from)
+            .map(MasterTable::from)
+            .map_err(decode_error),
+        4 => bitcode::decode::<v4::MasterTable>(payload)
+            .map(v5::MasterTable::from)
+            .map(v6::MasterTable::from)
+            .map(v7::MasterTable::from)
+            .map(MasterTable::from)
+            .map_err(decode_error),
+        3 => bitcode::decode::<v3::MasterTable>(payload)
+            .map(v4::MasterTable::from)
+            .map(v5::MasterTable::from)
+            .map(v6::MasterTable::from)
+            .map(v7::MasterTable::from)
+            .map(MasterTable::from)
+            .map_err(decode_error),
+        2 => bitcode::decode::<v2::MasterTable>(payload)
+            .map(v3::MasterTable::from)
+            .map(v4::MasterTable::from)
+            .map(v5::MasterTable::from)
+            .map(v6::MasterTable::from)
+            .map(v7::MasterTable::from)
+            .map(MasterTable::from)
+            .map_err(decode_error),
+        1 => bitcode::decode::<v1::MasterTable>(payload)
+            .map(v2::MasterTable::from)
+            .map(v3::MasterTable::from)
+            .map(v4::MasterTable::from)
+            .map(v5::MasterTable::from)
+            .map(v6::MasterTable::from)
+            .map(v7::MasterTable::from)
+            .map(MasterTable::from)
+            .map_err(decode_error),
+        v => Err(FormatError::UnsupportedVersion(v)),
+    }
+}
+
+/// Before rows kept their zone, character and timestamp.
+mod v1 {
+    use bitcode::{Decode, Encode};
+
+    use super::v6::Skill;
+
+    #[derive(Encode, Decode)]
+    pub struct Player {
+        pub id: u32,
+        pub name: String,
+        pub text: String,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct ItemSet {
+        pub id: u16,
+        pub name: String,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct TableRow {
+        pub boss_id: u8,
+        pub partition_id: u8,
+        pub ranking: u8,
+        pub player_id: u32,
+        pub dps: u32,
+        pub boss: bool,
+        pub skills: Vec<u16>,
+        pub armour: Vec<u16>,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+    }
+}
+
+impl From<v1::MasterTable> for v2::MasterTable {
+    fn from(old: v1::MasterTable) -> Self {
+        let rows = old
+            .rows
+            .into_iter()
+            .map(|r| v2::TableRow {
+                zone_id: boss_to_zone(r.boss_id),
+                boss_id: r.boss_id,
+                partition_id: r.partition_id,
+                ranking: r.ranking,
+                player_id: r.player_id,
+                character_id: 0,
+                dps: r.dps,
+                boss: r.boss,
+                timestamp: None,
+                skills: r.skills,
+                armour: r.armour,
+            })
+            .collect();
+
+        v2::MasterTable {
+            rows,
+            players: old.players,
+            characters: Vec::new(),
+            skills: old.skills,
+            sets: old.sets,
+        }
+    }
+}
+
+/// Before rows kept the slot, item id and icon of each gear piece.
+mod v2 {
+    use bitcode::{Decode, Encode};
+
+    use super::{v1::{ItemSet, Player}, v6::Skill};
+
+    #[derive(Encode, Decode)]
+    pub struct Character {
+        pub id: u32,
+        pub name: String,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct TableRow {
+        pub zone_id: u8,
+        pub boss_id: u8,
+        pub partition_id: u8,
+        pub ranking: u8,
+        pub player_id: u32,
+        pub character_id: u32,
+        pub dps: u32,
+        pub boss: bool,
+        pub timestamp: Option<u64>,
+        pub skills: Vec<u16>,
+        pub armour: Vec<u16>,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub characters: Vec<Character>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+    }
+}
+
+/// Slots are assumed from the list position, which holds unless the scraper
+/// skipped an empty slot.
+impl From<v2::MasterTable> for v3::MasterTable {
+    fn from(old: v2::MasterTable) -> Self {
+        let rows = old
+            .rows
+            .into_iter()
+            .map(|r| v3::TableRow {
+                zone_id: r.zone_id,
+                boss_id: r.boss_id,
+                partition_id: r.partition_id,
+                ranking: r.ranking,
+                player_id: r.player_id,
+                character_id: r.character_id,
+                dps: r.dps,
+                boss: r.boss,
+                timestamp: r.timestamp,
+                skills: r.skills,
+                gear: r
+                    .armour
+                    .into_iter()
+                    .enumerate()
+                    .map(|(slot, set_id)| v3::GearPiece { slot: slot as u8, set_id, item_id: 0, icon_id: 0 })
+                    .collect(),
+            })
+            .collect();
+
+        v3::MasterTable {
+            rows,
+            players: old.players,
+            characters: old.characters,
+            skills: old.skills,
+            sets: old.sets,
+            icons: Vec::new(),
+        }
+    }
+}
+
+/// Before rows kept their front and back bar weapon types.
+mod v3 {
+    use bitcode::{Decode, Encode};
+
+    use super::{v1::{ItemSet, Player}, v2::Character, v6::Skill};
+
+    #[derive(Encode, Decode, Clone)]
+    pub struct GearPiece {
+        pub slot: u8,
+        pub set_id: u16,
+        pub item_id: u32,
+        pub icon_id: u32,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct Icon {
+        pub id: u32,
+        pub path: String,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct TableRow {
+        pub zone_id: u8,
+        pub boss_id: u8,
+        pub partition_id: u8,
+        pub ranking: u8,
+        pub player_id: u32,
+        pub character_id: u32,
+        pub dps: u32,
+        pub boss: bool,
+        pub timestamp: Option<u64>,
+        pub skills: Vec<u16>,
+        pub gear: Vec<GearPiece>,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub characters: Vec<Character>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+        pub icons: Vec<Icon>,
+    }
+}
+
+/// Weapon types come from the stored icons, so tables migrated from version
+/// 2 or older have none.
+impl From<v3::MasterTable> for v4::MasterTable {
+    fn from(old: v3::MasterTable) -> Self {
+        let icons = &old.icons;
+        let bar = |gear: &[v3::GearPiece], (main_slot, off_slot): (u8, u8)| {
+            let icon = |slot| {
+                gear.iter()
+                    .find(|g| g.slot == slot)
+                    .and_then(|g| icons.iter().find(|i| i.id == g.icon_id))
+                    .map(|i| i.path.as_str())
+            };
+            v4::WeaponType::from(weapon_type_from_icons(icon(main_slot), icon(off_slot)))
+        };
+
+        let rows = old
+            .rows
+            .iter()
+            .map(|r| v4::TableRow {
+                zone_id: r.zone_id,
+                boss_id: r.boss_id,
+                partition_id: r.partition_id,
+                ranking: r.ranking,
+                player_id: r.player_id,
+                character_id: r.character_id,
+                dps: r.dps,
+                boss: r.boss,
+                timestamp: r.timestamp,
+                skills: r.skills.clone(),
+                gear: r.gear.clone(),
+                front_bar: bar(&r.gear, FRONT_BAR_SLOTS),
+                back_bar: bar(&r.gear, BACK_BAR_SLOTS),
+            })
+            .collect();
+
+        v4::MasterTable {
+            rows,
+            players: old.players,
+            characters: old.characters,
+            skills: old.skills,
+            sets: old.sets,
+            icons: old.icons,
+        }
+    }
+}
+
+/// Before rows kept their armour weight breakdown.
+mod v4 {
+    use bitcode::{Decode, Encode};
+
+    use super::{v1::{ItemSet, Player}, v2::Character, v3::{GearPiece, Icon}, v6::Skill};
+
+    #[derive(Encode, Decode, Clone, Copy)]
+    pub enum WeaponType {
+        Unknown,
+        DualWield,
+        TwoHanded,
+        OneHandAndShield,
+        Bow,
+        DestructionStaff,
+        RestorationStaff,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct TableRow {
+        pub zone_id: u8,
+        pub boss_id: u8,
+        pub partition_id: u8,
+        pub ranking: u8,
+        pub player_id: u32,
+        pub character_id: u32,
+        pub dps: u32,
+        pub boss: bool,
+        pub timestamp: Option<u64>,
+        pub skills: Vec<u16>,
+        pub gear: Vec<GearPiece>,
+        pub front_bar: WeaponType,
+        pub back_bar: WeaponType,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub characters: Vec<Character>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+        pub icons: Vec<Icon>,
+    }
+}
+
+/// Weights come from the stored icons, so tables migrated from version 2 or
+/// older have none.
+impl From<v4::MasterTable> for v5::MasterTable {
+    fn from(old: v4::MasterTable) -> Self {
+        let icons = &old.icons;
+
+        let rows = old
+            .rows
+            .iter()
+            .map(|r| v5::TableRow {
+                zone_id: r.zone_id,
+                boss_id: r.boss_id,
+                partition_id: r.partition_id,
+                ranking: r.ranking,
+                player_id: r.player_id,
+                character_id: r.character_id,
+                dps: r.dps,
+                boss: r.boss,
+                timestamp: r.timestamp,
+                skills: r.skills.clone(),
+                gear: r.gear.clone(),
+                front_bar: r.front_bar,
+                back_bar: r.back_bar,
+                armour_weights: v5::ArmourWeights::from(data::ArmourWeights::from_icons(
+                    r.gear
+                        .iter()
+                        .filter(|g| BODY_SLOTS.contains(&g.slot))
+                        .filter_map(|g| icons.iter().find(|i| i.id == g.icon_id))
+                        .map(|i| i.path.as_str()),
+                )),
+            })
+            .collect();
+
+        v5::MasterTable {
+            rows,
+            players: old.players,
+            characters: old.characters,
+            skills: old.skills,
+            sets: old.sets,
+            icons: old.icons,
+        }
+    }
+}
+
+/// Before rows kept the bar slot of each skill.
+mod v5 {
+    use bitcode::{Decode, Encode};
+
+    use super::{v1::{ItemSet, Player}, v2::Character, v3::{GearPiece, Icon}, v4::WeaponType, v6::Skill};
+
+    #[derive(Encode, Decode, Clone, Copy)]
+    pub struct ArmourWeights {
+        pub light: u8,
+        pub medium: u8,
+        pub heavy: u8,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct TableRow {
+        pub zone_id: u8,
+        pub boss_id: u8,
+        pub partition_id: u8,
+        pub ranking: u8,
+        pub player_id: u32,
+        pub character_id: u32,
+        pub dps: u32,
+        pub boss: bool,
+        pub timestamp: Option<u64>,
+        pub skills: Vec<u16>,
+        pub gear: Vec<GearPiece>,
+        pub front_bar: WeaponType,
+        pub back_bar: WeaponType,
+        pub armour_weights: ArmourWeights,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub characters: Vec<Character>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+        pub icons: Vec<Icon>,
+    }
+}
+
+/// Slots are assumed from the list position, which holds unless the row had
+/// an empty skill slot.
+impl From<v5::MasterTable> for v6::MasterTable {
+    fn from(old: v5::MasterTable) -> Self {
+        let rows = old
+            .rows
+            .into_iter()
+            .map(|r| v7::TableRow {
+                zone_id: r.zone_id,
+                boss_id: r.boss_id,
+                partition_id: r.partition_id,
+                ranking: r.ranking,
+                player_id: r.player_id,
+                character_id: r.character_id,
+                dps: r.dps,
+                boss: r.boss,
+                timestamp: r.timestamp,
+                skills: r
+                    .skills
+                    .into_iter()
+                    .enumerate()
+                    .map(|(slot, skill_id)| v7::SkillSlot { skill_id, slot: slot as u8 })
+                    .collect(),
+                gear: r.gear,
+                front_bar: r.front_bar,
+                back_bar: r.back_bar,
+                armour_weights: r.armour_weights,
+            })
+            .collect();
+
+        v6::MasterTable {
+            rows,
+            players: old.players,
+            characters: old.characters,
+            skills: old.skills,
+            sets: old.sets,
+            icons: old.icons,
+        }
+    }
+}
+
+/// Before skills kept their base skill.
+mod v6 {
+    use bitcode::{Decode, Encode};
+
+    use super::{v1::{ItemSet, Player}, v2::Character, v3::Icon, v7::TableRow};
+
+    #[derive(Encode, Decode)]
+    pub struct Skill {
+        pub id: u16,
+        pub name: String,
+        pub class: Option<String>,
+        pub tree: Option<String>,
+        pub display_name: Option<String>,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub characters: Vec<Character>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+        pub icons: Vec<Icon>,
+    }
+}
+
+/// The base skill is left empty; grouping falls back to the icon name until
+/// the table is rebuilt or merged with newer metadata.
+impl From<v6::MasterTable> for v7::MasterTable {
+    fn from(old: v6::MasterTable) -> Self {
+        let skills = old
+            .skills
+            .into_iter()
+            .map(|s| v7::Skill {
+                id: s.id,
+                name: s.name,
+                class: s.class,
+                tree: s.tree,
+                display_name: s.display_name,
+                base_skill: None,
+            })
+            .collect();
+
+        v7::MasterTable {
+            rows: old.rows,
+            players: old.players,
+            characters: old.characters,
+            skills,
+            sets: old.sets,
+            icons: old.icons,
+        }
+    }
+}
+
+/// Before rows kept the classes of their skills.
+mod v7 {
+    use bitcode::{Decode, Encode};
+
+    use super::{v1::{ItemSet, Player}, v2::Character, v3::{GearPiece, Icon}, v4::WeaponType, v5::ArmourWeights};
+
+    #[derive(Encode, Decode)]
+    pub struct Skill {
+        pub id: u16,
+        pub name: String,
+        pub class: Option<String>,
+        pub tree: Option<String>,
+        pub display_name: Option<String>,
+        pub base_skill: Option<String>,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct SkillSlot {
+        pub skill_id: u16,
+        pub slot: u8,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct TableRow {
+        pub zone_id: u8,
+        pub boss_id: u8,
+        pub partition_id: u8,
+        pub ranking: u8,
+        pub player_id: u32,
+        pub character_id: u32,
+        pub dps: u32,
+        pub boss: bool,
+        pub timestamp: Option<u64>,
+        pub skills: Vec<SkillSlot>,
+        pub gear: Vec<GearPiece>,
+        pub front_bar: WeaponType,
+        pub back_bar: WeaponType,
+        pub armour_weights: ArmourWeights,
+    }
+
+    #[derive(Encode, Decode)]
+    pub struct MasterTable {
+        pub rows: Vec<TableRow>,
+        pub players: Vec<Player>,
+        pub characters: Vec<Character>,
+        pub skills: Vec<Skill>,
+        pub sets: Vec<ItemSet>,
+        pub icons: Vec<Icon>,
+    }
+}
+
+impl From<data::WeaponType> for v4::WeaponType {
+    fn from(w: data::WeaponType) -> Self {
+        match w {
+            data::WeaponType::Unknown => v4::WeaponType::Unknown,
+            data::WeaponType::DualWield => v4::WeaponType::DualWield,
+            data::WeaponType::TwoHanded => v4::WeaponType::TwoHanded,
+            data::WeaponType::OneHandAndShield => v4::WeaponType::OneHandAndShield,
+            data::WeaponType::Bow => v4::WeaponType::Bow,
+            data::WeaponType::DestructionStaff => v4::WeaponType::DestructionStaff,
+            data::WeaponType::RestorationStaff => v4::WeaponType::RestorationStaff,
+        }
+    }
+}
+
+impl From<v4::WeaponType> for data::WeaponType {
+    fn from(w: v4::WeaponType) -> Self {
+        match w {
+            v4::WeaponType::Unknown => data::WeaponType::Unknown,
+            v4::WeaponType::DualWield => data::WeaponType::DualWield,
+            v4::WeaponType::TwoHanded => data::WeaponType::TwoHanded,
+            v4::WeaponType::OneHandAndShield => data::WeaponType::OneHandAndShield,
+            v4::WeaponType::Bow => data::WeaponType::Bow,
+            v4::WeaponType::DestructionStaff => data::WeaponType::DestructionStaff,
+            v4::WeaponType::RestorationStaff => data::WeaponType::RestorationStaff,
+        }
+    }
+}
+
+impl From<data::ArmourWeights> for v5::ArmourWeights {
+    fn from(w: data::ArmourWeights) -> Self {
+        v5::ArmourWeights { light: w.light, medium: w.medium, heavy: w.heavy }
+    }
+}
+
+impl From<v7::MasterTable> for MasterTable {
+    fn from(old: v7::MasterTable) -> Self {
+        let skills: Vec<data::Skill> = old
+            .skills
+            .into_iter()
+            .map(|s| data::Skill {
+                id: s.id,
+                name: s.name,
+                class: s.class,
+                tree: s.tree,
+                display_name: s.display_name,
+                base_skill: s.base_skill,
+            })
+            .collect();
+
+        let rows = old
+            .rows
+            .into_iter()
+            .map(|r| {
+                let slots: Vec<data::SkillSlot> = r
+                    .skills
+                    .into_iter()
+                    .map(|s| data::SkillSlot { skill_id: s.skill_id, slot: s.slot })
+                    .collect();
+                TableRow {
+                    zone_id: r.zone_id,
+                    boss_id: r.boss_id,
+                    partition_id: r.partition_id,
+                    ranking: r.ranking,
+                    player_id: r.player_id,
+                    character_id: r.character_id,
+                    dps: r.dps,
+                    boss: r.boss,
+                    timestamp: r.timestamp,
+                    skills: slots,
+                    gear: r
+                        .gear
+                        .into_iter()
+                        .map(|g| data::GearPiece { slot: g.slot, set_id: g.set_id, item_id: g.item_id, icon_id: g.icon_id })
+                        .collect(),
+                    front_bar: r.front_bar.into(),
+                    back_bar: r.back_bar.into(),
+                    armour_weights: data::ArmourWeights {
+                        light: r.armour_weights.light,
+                        medium: r.armour_weights.medium,
+                        heavy: r.armour_weights.heavy,
+                    },
+                    classes: Vec::new(),
+                }
+            })
+            .collect();
+
+        let mut master = MasterTable {
+            rows,
+            players: old.players.into_iter().map(|p| data::Player { id: p.id, name: p.name, text: p.text }).collect(),
+            characters: old.characters.into_iter().map(|c| data::Character { id: c.id, name: c.name }).collect(),
+            skills,
+            sets: old.sets.into_iter().map(|s| data::ItemSet { id: s.id, name: s.name }).collect(),
+            icons: old.icons.into_iter().map(|i| data::Icon { id: i.id, path: i.path }).collect(),
+            index: Default::default(),
+        };
+
+        // the classes weren't kept before this version
+        master.infer_row_classes();
+        master
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ArmourWeights, Class, GearPiece, SkillSlot, WeaponType};
+
+    fn players() -> Vec<v1::Player> {
+        vec![v1::Player { id: 1, name: "@PlayerOne".to_string(), text: String::new() }]
+    }
+
+    fn sets() -> Vec<v1::ItemSet> {
+        vec![v1::ItemSet { id: 5, name: "Slivers".to_string() }]
+    }
+
+    fn characters() -> Vec<v2::Character> {
+        vec![v2::Character { id: 1, name: "Char One".to_string() }]
+    }
+
+    fn old_skills() -> Vec<v6::Skill> {
+        vec![v6::Skill {
+            id: 1,
+            name: "ability_arcanist_018_a.png".to_string(),
+            class: Some("Arcanist".to_string()),
+            tree: Some("Herald of the Tome".to_string()),
+            display_name: Some("Glyphic of the Tides".to_string()),
+        }]
+    }
+
+    /// Main hand, off hand and head pieces, so the weapon and armour
+    /// migrations have icons to read.
+    fn gear() -> Vec<v3::GearPiece> {
+        vec![
+            v3::GearPiece { slot: 0, set_id: 5, item_id: 100, icon_id: 3 },
+            v3::GearPiece { slot: 4, set_id: 5, item_id: 101, icon_id: 1 },
+            v3::GearPiece { slot: 5, set_id: 5, item_id: 102, icon_id: 2 },
+        ]
+    }
+
+    fn icons() -> Vec<v3::Icon> {
+        vec![
+            v3::Icon { id: 1, path: "gear_breton_1hsword_d".to_string() },
+            v3::Icon { id: 2, path: "gear_breton_dagger_d".to_string() },
+            v3::Icon { id: 3, path: "gear_breton_medium_head_d".to_string() },
+        ]
+    }
+
+    fn decoded(version: u16, payload: Vec<u8>) -> MasterTable {
+        decode(&with_header(version, &payload)).unwrap_or_else(|e| panic!("version {}: {}", version, e))
+    }
+
+    /// Fields every version keeps.
+    fn check_common(master: &MasterTable) {
+        assert_eq!(master.rows.len(), 1);
+        let row = &master.rows[0];
+        assert_eq!((row.zone_id, row.boss_id, row.partition_id, row.ranking), (17, 55, 27, 3));
+        assert_eq!((row.player_id, row.dps, row.boss), (1, 150000, true));
+        assert_eq!(row.skills, vec![SkillSlot { skill_id: 1, slot: 0 }]);
+        assert_eq!(row.classes, vec![Class::Arcanist]);
+        assert_eq!(master.players[0].name, "@PlayerOne");
+        assert_eq!(master.sets[0].name, "Slivers");
+        assert_eq!(master.skills[0].display_name.as_deref(), Some("Glyphic of the Tides"));
+    }
+
+    /// Fields of version 3 and later, where gear kept slots and icons.
+    fn check_gear(master: &MasterTable) {
+        let row = &master.rows[0];
+        assert_eq!(row.character_id, 1);
+        assert_eq!(row.timestamp, Some(1712345678000));
+        assert_eq!(row.gear[1], GearPiece { slot: 4, set_id: 5, item_id: 101, icon_id: 1 });
+        assert_eq!(row.front_bar, WeaponType::DualWield);
+        assert_eq!(row.back_bar, WeaponType::Unknown);
+        assert_eq!(row.armour_weights, ArmourWeights { light: 0, medium: 1, heavy: 0 });
+        assert_eq!(master.icons.len(), 3);
+        assert_eq!(master.characters[0].name, "Char One");
+    }
+
+    fn v1_table() -> v1::MasterTable {
+        v1::MasterTable {
+            rows: vec![v1::TableRow {
+                boss_id: 55,
+                partition_id: 27,
+                ranking: 3,
+                player_id: 1,
+                dps: 150000,
+                boss: true,
+                skills: vec![1],
+                armour: vec![5, 5],
+            }],
+            players: players(),
+            skills: old_skills(),
+            sets: sets(),
+        }
+    }
+
+    #[test]
+    fn decodes_headerless_v1() {
+        let master = decode(&bitcode::encode(&v1_table())).unwrap();
+        check_common(&master);
+        let row = &master.rows[0];
+        assert_eq!((row.character_id, row.timestamp), (0, None));
+        assert_eq!(row.gear[1], GearPiece { slot: 1, set_id: 5, item_id: 0, icon_id: 0 });
+        assert_eq!(row.front_bar, WeaponType::Unknown);
+        assert_eq!(row.armour_weights, ArmourWeights::default());
+    }
+
+    #[test]
+    fn decodes_v1() {
+        check_common(&decoded(1, bitcode::encode(&v1_table())));
+    }
+
+    #[test]
+    fn decodes_v2() {
+        let table = v2::MasterTable {
+            rows: vec![v2::TableRow {
+                zone_id: 17,
+                boss_id: 55,
+                partition_id: 27,
+                ranking: 3,
+                player_id: 1,
+                character_id: 1,
+                dps: 150000,
+                boss: true,
+                timestamp: Some(1712345678000),
+                skills: vec![1],
+                armour: vec![5, 5],
+            }],
+            players: players(),
+            characters: characters(),
+            skills: old_skills(),
+            sets: sets(),
+        };
+
+        let master = decoded(2, bitcode::encode(&table));
+        check_common(&master);
+        assert_eq!(master.rows[0].character_id, 1);
+        assert_eq!(master.rows[0].gear.len(), 2);
+        assert!(master.icons.is_empty());
+    }
+
+    fn v3_row() -> v3::TableRow {
+        v3::TableRow {
+            zone_id: 17,
+            boss_id: 55,
+            partition_id: 27,
+            ranking: 3,
+            player_id: 1,
+            character_id: 1,
+            dps: 150000,
+            boss: true,
+            timestamp: Some(1712345678000),
+            skills: vec![1],
+            gear: gear(),
+        }
+    }
+
+    fn v3_table() -> v3::MasterTable {
+        v3::MasterTable {
+            rows: vec![v3_row()],
+            players: players(),
+            characters: characters(),
+            skills: old_skills(),
+            sets: sets(),
+            icons: icons(),
+        }
+    }
+
+    #[test]
+    fn decodes_v3() {
+        let master = decoded(3, bitcode::encode(&v3_table()));
+        check_common(&master);
+        check_gear(&master);
+    }
+
+    #[test]
+    fn decodes_v4() {
+        let master = decoded(4, bitcode::encode(&v4::MasterTable::from(v3_table())));
+        check_common(&master);
+        check_gear(&master);
+    }
+
+    #[test]
+    fn decodes_v5() {
+        let v5 = v5::MasterTable::from(v4::MasterTable::from(v3_table()));
+        let master = decoded(5, bitcode::encode(&v5));
+        check_common(&master);
+        check_gear(&master);
+    }
+
+    #[test]
+    fn decodes_v6() {
+        let v6 = v6::MasterTable::from(v5::MasterTable::from(v4::MasterTable::from(v3_table())));
+        let master = decoded(6, bitcode::encode(&v6));
+        check_common(&master);
+        check_gear(&master);
+        assert_eq!(master.skills[0].base_skill, None);
+    }
+
+    #[test]
+    fn decodes_v7() {
+        let mut v7 = v7::MasterTable::from(v6::MasterTable::from(v5::MasterTable::from(v4::MasterTable::from(v3_table()))));
+        v7.skills[0].base_skill = Some("Glyphic".to_string());
+        let master = decoded(7, bitcode::encode(&v7));
+        check_common(&master);
+        check_gear(&master);
+        assert_eq!(master.skills[0].base_skill.as_deref(), Some("Glyphic"));
+    }
+
+    #[test]
+    fn round_trips_current() {
+        let master = decoded(7, bitcode::encode(&v7::MasterTable::from(v6::MasterTable::from(v5::MasterTable::from(
+            v4::MasterTable::from(v3_table()),
+        )))));
+        // migrated tables come out indexed, so compare against an indexed decode
+        assert_eq!(data::decode_master_table(&encode(&master)).unwrap(), master);
+    }
+
+    #[test]
+    fn rejects_bad_checksum_and_newer_versions() {
+        let mut bytes = encode(&decode(&bitcode::encode(&v1_table())).unwrap());
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(matches!(decode(&bytes), Err(FormatError::ChecksumMismatch { .. })));
+
+        let newer = with_header(CURRENT_VERSION + 1, &[]);
+        assert_eq!(decode(&newer), Err(FormatError::UnsupportedVersion(CURRENT_VERSION + 1)));
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::data::{Character, ItemSet, MasterTable, Player, Skill, TableRow};
+
+/// Lookups over a master table, rebuilt by `MasterTable::reindex` whenever
+/// the rows or tables change. Row lists hold positions into `master.rows`
+/// in table order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TableIndex {
+    rows_by_partition: BTreeMap<u8, Vec<usize>>,
+    rows_by_boss: BTreeMap<u8, Vec<usize>>,
+    rows_by_player: HashMap<u32, Vec<usize>>,
+    skills: HashMap<u16, usize>,
+    sets: HashMap<u16, usize>,
+    players: HashMap<u32, usize>,
+    characters: HashMap<u32, usize>,
+}
+
+impl TableIndex {
+    pub fn build(master: &MasterTable) -> TableIndex {
+        let mut index = TableIndex::default();
+
+        for (i, row) in master.rows.iter().enumerate() {
+            index.rows_by_partition.entry(row.partition_id).or_default().push(i);
+            index.rows_by_boss.entry(row.boss_id).or_default().push(i);
+            index.rows_by_player.entry(row.player_id).or_default().push(i);
+        }
+
+        index.skills = master.skills.iter().enumerate().map(|(i, s)| (s.id, i)).collect();
+        index.sets = master.sets.iter().enumerate().map(|(i, s)| (s.id, i)).collect();
+        index.players = master.players.iter().enumerate().map(|(i, p)| (p.id, i)).collect();
+        index.characters = master.characters.iter().enumerate().map(|(i, c)| (c.id, i)).collect();
+
+        index
+    }
+}
+
+impl MasterTable {
+    /// Rebuilds `index` after the table has been changed.
+    pub fn reindex(&mut self) {
+        self.index = TableIndex::build(self);
+    }
+
+    /// Adds a skill and indexes it, for tables that are still being built.
+    pub(crate) fn push_skill(&mut self, skill: Skill) {
+        self.index.skills.insert(skill.id, self.skills.len());
+        self.skills.push(skill);
+    }
+
+    pub fn skill(&self, id: u16) -> Option<&Skill> {
+        self.index.skills.get(&id).map(|&i| &self.skills[i])
+    }
+
+    pub fn set(&self, id: u16) -> Option<&ItemSet> {
+        self.index.sets.get(&id).map(|&i| &self.sets[i])
+    }
+
+    pub fn player(&self, id: u32) -> Option<&Player> {
+        self.index.players.get(&id).map(|&i| &self.players[i])
+    }
+
+    pub fn character(&self, id: u32) -> Option<&Character> {
+        self.index.characters.get(&id).map(|&i| &self.characters[i])
+    }
+
+    /// Every partition with rows, in partition order.
+    pub fn partition_ids(&self) -> impl Iterator<Item = u8> + '_ {
+        self.index.rows_by_partition.keys().copied()
+    }
+
+    /// Every boss with rows, in boss order.
+    pub fn boss_ids(&self) -> impl Iterator<Item = u8> + '_ {
+        self.index.rows_by_boss.keys().copied()
+    }
+
+    /// Positions in `rows` of the rows in a partition, in table order.
+    pub fn partition_positions(&self, partition_id: u8) -> &[usize] {
+        self.index.rows_by_partition.get(&partition_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Positions in `rows` of the rows for a boss, in table order.
+    pub fn boss_positions(&self, boss_id: u8) -> &[usize] {
+        self.index.rows_by_boss.get(&boss_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every row for a player, in table order.
+    pub fn rows_for_player(&self, player_id: u32) -> impl Iterator<Item = &TableRow> + '_ {
+        self.index
+            .rows_by_player
+            .get(&player_id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|&i| &self.rows[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::tests::{row, table};
+    use crate::data::{Character, Class, Player, TableRow, infer_classes};
+
+    #[test]
+    fn lookups_find_ids_that_are_not_positions() {
+        let rows = vec![
+            row(27, 1, 100, &[1], &[]),
+            TableRow { boss_id: 56, player_id: 7, ..row(28, 1, 100, &[2], &[]) },
+            TableRow { player_id: 7, ..row(27, 2, 100, &[3], &[]) },
+        ];
+        let mut master = table(rows);
+        master.skills.reverse();
+        master.players.push(Player { id: 7, name: "@PlayerSeven".to_string(), text: String::new() });
+        master.characters = vec![Character { id: 4, name: "Seven's Arcanist".to_string() }];
+        master.reindex();
+
+        assert_eq!(master.skill(1).map(|s| s.id), Some(1));
+        assert_eq!(master.skill(4).map(|s| s.id), Some(4));
+        assert!(master.skill(0).is_none() && master.skill(5).is_none());
+        assert_eq!(infer_classes(&master, &master.rows[1].skills), vec![Class::Templar]);
+        assert_eq!(master.set(3).map(|s| s.name.as_str()), Some("Relequen"));
+        assert!(master.set(0).is_none());
+        assert_eq!(master.player(7).map(|p| p.name.as_str()), Some("@PlayerSeven"));
+        assert!(master.player(0).is_none() && master.player(2).is_none());
+        assert_eq!(master.character(4).map(|c| c.name.as_str()), Some("Seven's Arcanist"));
+        assert!(master.character(1).is_none());
+
+        assert_eq!(master.partition_ids().collect::<Vec<_>>(), vec![27, 28]);
+        assert_eq!(master.boss_ids().collect::<Vec<_>>(), vec![55, 56]);
+        assert_eq!(master.partition_positions(27), &[0, 2]);
+        assert_eq!(master.boss_positions(56), &[1]);
+        assert!(master.partition_positions(29).is_empty());
+        let rankings: Vec<(u8, u8)> = master.rows_for_player(7).map(|r| (r.partition_id, r.ranking)).collect();
+        assert_eq!(rankings, vec![(28, 1), (27, 2)]);
+        assert_eq!(master.rows_for_player(2).count(), 0);
+    }
+}
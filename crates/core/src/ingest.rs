@@ -0,0 +1,418 @@
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+use crate::{
+    data::{
+        FieldError, IngestError, IngestMode, IngestReport, MasterTable, Row, SkillMetadata, TableBuilder, check_set_tables,
+        parse_csv_text, parse_mined_skills_text, parse_partition_csv_text, parse_set_data_text, parse_set_ids_text, parse_skill_metadata,
+        unknown_skills,
+    },
+    format,
+    index::TableIndex,
+    names::{HodorPlayer, parse_names},
+};
+
+/// Input locations for a master table build. The defaults match the layout of
+/// the `data/` folder in this repository.
+#[derive(Debug, Clone)]
+pub struct IngestConfig {
+    /// merged CSV, or the root of a `zone_*/boss_*/partition_*.csv` tree
+    pub total_dps: PathBuf,
+    pub boss_dps: PathBuf,
+    pub set_data: PathBuf,
+    pub set_ids: PathBuf,
+    pub skills: PathBuf,
+    /// extra skill metadata for icons `skills` doesn't know, see
+    /// `read_skill_metadata`
+    pub extra_skills: Vec<PathBuf>,
+    /// LibCustomNames addon folder, skipped when `None`
+    pub names_dir: Option<PathBuf>,
+    /// server folders inside the addon to read names from, e.g. `PC` or `PC-EU`
+    pub name_regions: Vec<String>,
+    pub mode: IngestMode,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        IngestConfig {
+            total_dps: PathBuf::from("data/total_dps.csv"),
+            boss_dps: PathBuf::from("data/boss_dps.csv"),
+            set_data: PathBuf::from("data/set_data.csv"),
+            set_ids: PathBuf::from("data/set_ids.csv"),
+            skills: PathBuf::from("data/skills.json"),
+            extra_skills: Vec::new(),
+            names_dir: None,
+            name_regions: vec!["PC".to_string()],
+            mode: IngestMode::Strict,
+        }
+    }
+}
+
+fn read_input(path: &Path) -> Result<String, IngestError> {
+    fs::read_to_string(path).map_err(|e| IngestError::Io { file: path.to_path_buf(), message: e.to_string() })
+}
+
+/// Children of `dir` named `{prefix}{number}{suffix}`, sorted by number so ids
+/// are handed out in the same order on every run.
+fn numbered_entries(dir: &Path, prefix: &'static str, suffix: &str, report: &mut IngestReport) -> Result<Vec<(u32, PathBuf)>, IngestError> {
+    let entries = fs::read_dir(dir).map_err(|e| IngestError::Io { file: dir.to_path_buf(), message: e.to_string() })?;
+
+    let mut numbered = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| IngestError::Io { file: dir.to_path_buf(), message: e.to_string() })?.path();
+        let Some(number) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix(prefix))
+            .and_then(|n| n.strip_suffix(suffix))
+        else {
+            continue;
+        };
+        match number.parse::<u32>() {
+            Ok(n) => numbered.push((n, path)),
+            Err(_) => {
+                let error = FieldError::Invalid { field: prefix.trim_end_matches('_'), value: number.to_string() };
+                report.record(IngestError::Path { file: path, error })?;
+            }
+        }
+    }
+
+    numbered.sort();
+    Ok(numbered)
+}
+
+/// Reads the tree the scraper writes, `{root}/zone_{zone}/boss_{boss}/partition_{partition}.csv`,
+/// taking the zone, boss and partition of every row from its path.
+pub fn read_partition_tree(root: &Path, report: &mut IngestReport) -> Result<Vec<Row>, IngestError> {
+    let mut rows = Vec::new();
+    for (zone, zone_dir) in numbered_entries(root, "zone_", "", report)? {
+        for (boss, boss_dir) in numbered_entries(&zone_dir, "boss_", "", report)? {
+            for (partition, file) in numbered_entries(&boss_dir, "partition_", ".csv", report)? {
+                let text = read_input(&file)?;
+                rows.extend(parse_partition_csv_text(&text, &file, (zone, boss, partition), report)?);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Rows from either a merged CSV file or a scraper output tree.
+pub fn read_rows(path: &Path, report: &mut IngestReport) -> Result<Vec<Row>, IngestError> {
+    if path.is_dir() {
+        read_partition_tree(path, report)
+    } else {
+        parse_csv_text(&read_input(path)?, path, report)
+    }
+}
+
+/// Metadata from `config.skills`, with any gaps filled from the supplementary
+/// files in order. A `.json` file has the `skills.json` layout, anything
+/// else is read as a `minedSkills` CSV export.
+fn read_skill_metadata(config: &IngestConfig, report: &mut IngestReport) -> Result<HashMap<String, SkillMetadata>, IngestError> {
+    let mut meta_lookup = parse_skill_metadata(&read_input(&config.skills)?, &config.skills, report)?;
+
+    for path in &config.extra_skills {
+        let text = read_input(path)?;
+        let extra = if path.extension().is_some_and(|e| e == "json") {
+            parse_skill_metadata(&text, path, report)?
+        } else {
+            parse_mined_skills_text(&text, path, report)?
+        };
+        for (icon, meta) in extra {
+            meta_lookup.entry(icon).or_insert(meta);
+        }
+    }
+
+    Ok(meta_lookup)
+}
+
+/// Folders holding the name files, `{dir}/{region}/names` for every region,
+/// or `dir` itself when it already is a `names` folder.
+pub fn name_folders(dir: &Path, regions: &[String]) -> Vec<PathBuf> {
+    if dir.file_name().is_some_and(|n| n == "names") {
+        return vec![dir.to_path_buf()];
+    }
+    regions.iter().map(|region| dir.join(region).join("names")).collect()
+}
+
+/// Reads every name file for the given regions. A missing folder or a file
+/// that fails to read or parse is an error in strict mode and skipped in
+/// lenient mode.
+pub fn read_players_from_folders(dir: &Path, regions: &[String], report: &mut IngestReport) -> Result<Vec<HodorPlayer>, IngestError> {
+    let mut players = Vec::new();
+
+    for folder in name_folders(dir, regions) {
+        let entries = match fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(e) => {
+                report.record(IngestError::Io { file: folder, message: e.to_string() })?;
+                continue;
+            }
+        };
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+
+        for path in files {
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    report.record(IngestError::Io { file: path, message: e.to_string() })?;
+                    continue;
+                }
+            };
+
+            match parse_names(&content) {
+                Ok(found) => players.extend(found),
+                Err(e) => {
+                    let error = FieldError::Invalid { field: "lua", value: e.message };
+                    report.record(IngestError::Parse { file: path, line: e.line, error })?;
+                }
+            }
+        }
+    }
+
+    Ok(players)
+}
+
+/// Builds the master table from the files in `config`. In lenient mode bad
+/// lines are skipped and listed in the returned report instead of failing.
+pub fn build_master_table(config: &IngestConfig) -> Result<(MasterTable, IngestReport), IngestError> {
+    let empty = MasterTable {
+        rows: Vec::new(),
+        players: Vec::new(),
+        characters: Vec::new(),
+        skills: Vec::new(),
+        sets: Vec::new(),
+        icons: Vec::new(),
+        index: TableIndex::default(),
+    };
+    merge_into_master_table(empty, config)
+}
+
+/// Adds the rows in `config` to an existing table. Players and skills keep the
+/// ids they already have, new ones are appended, and rows for a (boss,
+/// partition, ranking, ST/AOE) key the table already has are refused.
+pub fn merge_into_master_table(existing: MasterTable, config: &IngestConfig) -> Result<(MasterTable, IngestReport), IngestError> {
+    let mut report = IngestReport::new(config.mode);
+
+    let set_ids_text = read_input(&config.set_ids)?;
+    let set_id_hashmap = parse_set_ids_text(&set_ids_text, &config.set_ids, &mut report)?;
+
+    // read input CSVs, or the per-partition trees they were merged from
+    let rows_total_dps = read_rows(&config.total_dps, &mut report)?;
+    let rows_boss_dps = read_rows(&config.boss_dps, &mut report)?;
+
+    let sets_vec = parse_set_data_text(&read_input(&config.set_data)?, &config.set_data, &mut report)?;
+    report.set_issues = check_set_tables(&sets_vec, &set_ids_text);
+
+    let meta_lookup = read_skill_metadata(config, &mut report)?;
+
+    let mut builder = TableBuilder::new(existing, &meta_lookup, &set_id_hashmap, &sets_vec);
+
+    for entry in &rows_total_dps {
+        builder.process_entry(entry, false, &config.total_dps, &mut report)?;
+    }
+    for entry in &rows_boss_dps {
+        builder.process_entry(entry, true, &config.boss_dps, &mut report)?;
+    }
+
+    let TableBuilder { mut master, player_id_map, unmapped_gear, .. } = builder;
+    master.sets = sets_vec;
+
+    report.unmapped_gear = unmapped_gear.into_values().collect();
+    report.unmapped_gear.sort_by(|a, b| b.count.cmp(&a.count).then(a.item_id.cmp(&b.item_id)));
+    report.unknown_skills = unknown_skills(&master);
+
+    if let Some(hodor_dir) = &config.names_dir {
+        let hodor_players = read_players_from_folders(hodor_dir, &config.name_regions, &mut report)?;
+
+        for hp in hodor_players {
+            match player_id_map.get(&hp.name) {
+                Some(&pid) => {
+                    if let Some(existing) = master.players.iter_mut().find(|p| p.id == pid) {
+                        existing.text = hp.text;
+                    }
+                    report.names_matched += 1;
+                }
+                None => report.names_ignored += 1,
+            }
+        }
+    }
+
+    master.reindex();
+
+    Ok((master, report))
+}
+
+/// Writes the versioned table to `bin_path`, plus a pretty printed copy to
+/// `json_path` for reading by hand.
+pub fn write_master_table(master: &MasterTable, bin_path: &Path, json_path: Option<&Path>) -> Result<(), IngestError> {
+    let encoded = format::encode(master);
+    fs::write(bin_path, &encoded)
+        .map_err(|e| IngestError::Io { file: bin_path.to_path_buf(), message: e.to_string() })?;
+
+    if let Some(json_path) = json_path {
+        let json_text = serde_json::to_string_pretty(master)
+            .map_err(|e| IngestError::Json { file: json_path.to_path_buf(), message: e.to_string() })?;
+        fs::write(json_path, json_text.as_bytes())
+            .map_err(|e| IngestError::Io { file: json_path.to_path_buf(), message: e.to_string() })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Class, RowKey};
+
+    /// A fresh folder under the system temp dir, removed again on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir = std::env::temp_dir().join(format!("parselist-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn write(&self, path: &str, text: &str) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn regions(list: &[&str]) -> Vec<String> {
+        list.iter().map(|r| r.to_string()).collect()
+    }
+
+    fn names(players: &[HodorPlayer]) -> Vec<&str> {
+        players.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn name_folders_per_region_or_names_folder() {
+        let addon = Path::new("AddOns/LibCustomNames");
+        assert_eq!(
+            name_folders(addon, &regions(&["PC", "PC-EU"])),
+            vec![addon.join("PC").join("names"), addon.join("PC-EU").join("names")]
+        );
+
+        let names_dir = addon.join("PC").join("names");
+        assert_eq!(name_folders(&names_dir, &regions(&["PC", "PC-EU"])), vec![names_dir.clone()]);
+    }
+
+    #[test]
+    fn reads_every_region_in_file_order() {
+        let dir = TempDir::new("names-regions");
+        dir.write("PC/names/b.lua", r#"n["@Two"] = {"Two", "Second"}"#);
+        dir.write("PC/names/a.lua", r#"n["@One"] = {"One", "First"}"#);
+        dir.write("PC-EU/names/a.lua", r#"n["@Three"] = {"Three", "Third"}"#);
+        dir.write("XB/names/a.lua", r#"n["@Four"] = {"Four", "Not read"}"#);
+
+        let mut report = IngestReport::new(IngestMode::Strict);
+        let players = read_players_from_folders(&dir.0, &regions(&["PC", "PC-EU"]), &mut report).unwrap();
+        assert_eq!(names(&players), vec!["One", "Two", "Three"]);
+
+        // pointing straight at a names folder ignores the regions
+        let players = read_players_from_folders(&dir.0.join("PC-EU").join("names"), &regions(&["PC"]), &mut report).unwrap();
+        assert_eq!(names(&players), vec!["Three"]);
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn lenient_mode_skips_bad_files_and_missing_regions() {
+        let dir = TempDir::new("names-lenient");
+        dir.write("PC/names/a.lua", r#"n["@One"] = {"One", "First"}"#);
+        dir.write("PC/names/b.lua", "n[\"@Two\"] = {\"Two\",\n\"Unterminated}");
+
+        let mut report = IngestReport::new(IngestMode::Lenient);
+        let players = read_players_from_folders(&dir.0, &regions(&["PC", "PC-EU"]), &mut report).unwrap();
+        assert_eq!(names(&players), vec!["One"]);
+
+        let files: Vec<PathBuf> = report.errors.iter().map(|e| e.file().to_path_buf()).collect();
+        assert_eq!(files, vec![dir.0.join("PC/names/b.lua"), dir.0.join("PC-EU").join("names")]);
+        assert!(matches!(report.errors[0], IngestError::Parse { line: 2, .. }));
+        assert!(matches!(report.errors[1], IngestError::Io { .. }));
+
+        let mut report = IngestReport::new(IngestMode::Strict);
+        let error = read_players_from_folders(&dir.0, &regions(&["PC-EU"]), &mut report).unwrap_err();
+        assert!(matches!(error, IngestError::Io { .. }));
+    }
+
+    const HEADER: &str = "zone,boss,partition,row_id,name,at_name,dps,timestamp,talents,gear\n";
+
+    /// A row of the merged CSV for zone 17 and boss 55.
+    fn csv_row(partition: u8, ranking: u8, at_name: &str, skills: &[&str]) -> String {
+        let talents: Vec<String> = skills.iter().map(|s| format!("'ability_{}.png'", s)).collect();
+        format!("17,55,{},row-{},Char,{},150000,,\"[{}]\",\"[]\"\n", partition, ranking, at_name, talents.join(", "))
+    }
+
+    #[test]
+    fn merge_keeps_ids_and_refuses_rows_already_in_the_table() {
+        let dir = TempDir::new("merge");
+        dir.write("set_ids.csv", "1,101,102\n");
+        dir.write("set_data.csv", "1,Slivers\n");
+        dir.write(
+            "skills.json",
+            r#"{
+                "0": {"fileName": "ability_arcanist_018_a.png", "skillName": "Glyphic of the Tides", "skillClass": "Arcanist", "skillTree": "Curative Runeforms"},
+                "1": {"fileName": "ability_templar_001.png", "skillName": "Crescent Sweep", "skillClass": "Templar", "skillTree": "Aedric Spear"}
+            }"#,
+        );
+        let first = HEADER.to_string() + &csv_row(27, 1, "@One", &["arcanist_018_a"]);
+        dir.write("first/total.csv", &first);
+        dir.write("first/boss.csv", &first);
+        dir.write(
+            "second/total.csv",
+            &(HEADER.to_string() + &csv_row(27, 2, "@Two", &["templar_001", "arcanist_018_a"]) + &csv_row(28, 1, "@One", &["arcanist_018_a"])),
+        );
+        // already in the table from the first build
+        dir.write("second/boss.csv", &(HEADER.to_string() + &csv_row(27, 1, "@Two", &["templar_001"])));
+
+        let config = |inputs: &str, mode| IngestConfig {
+            total_dps: dir.0.join(inputs).join("total.csv"),
+            boss_dps: dir.0.join(inputs).join("boss.csv"),
+            set_data: dir.0.join("set_data.csv"),
+            set_ids: dir.0.join("set_ids.csv"),
+            skills: dir.0.join("skills.json"),
+            mode,
+            ..IngestConfig::default()
+        };
+        let build = || build_master_table(&config("first", IngestMode::Strict)).unwrap().0;
+
+        let error = merge_into_master_table(build(), &config("second", IngestMode::Strict)).unwrap_err();
+        let key = RowKey { boss_id: 55, partition_id: 27, ranking: 1, boss: true };
+        assert_eq!(error, IngestError::Duplicate { file: dir.0.join("second/boss.csv"), key });
+
+        let (master, report) = merge_into_master_table(build(), &config("second", IngestMode::Lenient)).unwrap();
+        assert_eq!(report.rows_skipped, 1);
+        assert_eq!(report.errors, vec![error]);
+
+        // existing ids are kept and new ones appended
+        let players: Vec<(u32, &str)> = master.players.iter().map(|p| (p.id, p.name.as_str())).collect();
+        assert_eq!(players, vec![(1, "One"), (2, "Two")]);
+        let skills: Vec<(u16, &str)> = master.skills.iter().map(|s| (s.id, s.name.as_str())).collect();
+        assert_eq!(skills, vec![(1, "arcanist_018_a"), (2, "templar_001")]);
+        let rows: Vec<(u8, u8, bool, u32)> = master.rows.iter().map(|r| (r.partition_id, r.ranking, r.boss, r.player_id)).collect();
+        assert_eq!(rows, vec![(27, 1, false, 1), (27, 1, true, 1), (27, 2, false, 2), (28, 1, false, 1)]);
+        assert_eq!(master.rows[2].classes, vec![Class::Arcanist, Class::Templar]);
+
+        // and the index covers the merged rows
+        assert_eq!(master.player(2).map(|p| p.name.as_str()), Some("Two"));
+        assert_eq!(master.skill(2).and_then(|s| s.class.as_deref()), Some("Templar"));
+        assert_eq!(master.partition_positions(28), &[3]);
+        assert_eq!(master.rows_for_player(1).count(), 3);
+    }
+}
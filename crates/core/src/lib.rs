@@ -0,0 +1,9 @@
+pub mod builds;
+pub mod data;
+pub mod dates;
+pub mod filter;
+pub mod format;
+pub mod index;
+pub mod ingest;
+pub mod names;
+pub mod query;
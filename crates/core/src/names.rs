@@ -0,0 +1,366 @@
+/// One entry of a LibCustomNames name table, e.g.
+/// `n["@Name"] = {"Name", "|cFFD700Custom Name|r"}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HodorPlayer {
+    /// @name without the `@`
+    pub name: String,
+    /// second string of the entry, the formatted name shown in game
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(String),
+    Punct(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuaError {
+    pub line: usize,
+    pub message: String,
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Lexer { chars: src.chars().peekable(), line: 1 }
+    }
+
+    fn error(&self, message: &str) -> LuaError {
+        LuaError { line: self.line, message: message.to_string() }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    /// Counts the `=` of a long bracket after its first `[`, consuming the
+    /// second `[`. `None` if this isn't a long bracket.
+    fn long_bracket_level(&mut self) -> Option<usize> {
+        let mut probe = self.chars.clone();
+        let mut level = 0;
+        while probe.peek() == Some(&'=') {
+            probe.next();
+            level += 1;
+        }
+        if probe.peek() != Some(&'[') {
+            return None;
+        }
+        for _ in 0..=level {
+            self.bump();
+        }
+        Some(level)
+    }
+
+    fn long_string(&mut self, level: usize) -> Result<String, LuaError> {
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                Some(']') => {
+                    let mut probe = self.chars.clone();
+                    let mut closing = 0;
+                    while probe.peek() == Some(&'=') {
+                        probe.next();
+                        closing += 1;
+                    }
+                    if closing == level && probe.peek() == Some(&']') {
+                        for _ in 0..=level {
+                            self.bump();
+                        }
+                        return Ok(out);
+                    }
+                    out.push(']');
+                }
+                Some(c) => out.push(c),
+                None => return Err(self.error("unterminated long string")),
+            }
+        }
+    }
+
+    /// Lua strings are byte strings, so escapes are collected as bytes and
+    /// the whole string has to decode as UTF-8, e.g. `\195\169` is `é`.
+    fn quoted_string(&mut self, quote: char) -> Result<String, LuaError> {
+        let mut out: Vec<u8> = Vec::new();
+        let line = self.line;
+        loop {
+            // checked before consuming so the error points at the string's line
+            if self.chars.peek() == Some(&'\n') {
+                return Err(self.error("unterminated string"));
+            }
+            match self.bump() {
+                Some(c) if c == quote => break,
+                None => return Err(self.error("unterminated string")),
+                Some('\\') => match self.bump() {
+                    Some('n') => out.push(b'\n'),
+                    Some('t') => out.push(b'\t'),
+                    Some('r') => out.push(b'\r'),
+                    Some('x') => {
+                        let hex: String = (0..2).filter_map(|_| self.bump()).collect();
+                        let code = u8::from_str_radix(&hex, 16).map_err(|_| self.error("invalid \\x escape"))?;
+                        out.push(code);
+                    }
+                    Some(d) if d.is_ascii_digit() => {
+                        let mut digits = d.to_string();
+                        while digits.len() < 3 && self.chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                            digits.push(self.bump().unwrap_or('0'));
+                        }
+                        let code: u8 = digits.parse().map_err(|_| self.error("invalid decimal escape"))?;
+                        out.push(code);
+                    }
+                    Some('z') => {
+                        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+                            self.bump();
+                        }
+                    }
+                    Some(c) => push_char(&mut out, c),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(c) => push_char(&mut out, c),
+            }
+        }
+        String::from_utf8(out).map_err(|_| LuaError { line, message: "string is not valid UTF-8".to_string() })
+    }
+
+    fn tokens(mut self) -> Result<Vec<(usize, Token)>, LuaError> {
+        let mut tokens = Vec::new();
+
+        while let Some(&c) = self.chars.peek() {
+            let line = self.line;
+            match c {
+                c if c.is_whitespace() => {
+                    self.bump();
+                }
+                '-' => {
+                    self.bump();
+                    if self.chars.peek() != Some(&'-') {
+                        tokens.push((line, Token::Punct('-')));
+                        continue;
+                    }
+                    self.bump();
+                    if self.chars.peek() == Some(&'[') {
+                        self.bump();
+                        if let Some(level) = self.long_bracket_level() {
+                            self.long_string(level)?;
+                            continue;
+                        }
+                    }
+                    while self.chars.peek().is_some_and(|&c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                '"' | '\'' => {
+                    self.bump();
+                    tokens.push((line, Token::Str(self.quoted_string(c)?)));
+                }
+                '[' => {
+                    self.bump();
+                    match self.long_bracket_level() {
+                        Some(level) => tokens.push((line, Token::Str(self.long_string(level)?))),
+                        None => tokens.push((line, Token::Punct('['))),
+                    }
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut ident = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        ident.push(c);
+                        self.bump();
+                    }
+                    tokens.push((line, Token::Ident(ident)));
+                }
+                c if c.is_ascii_digit() => {
+                    let mut number = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '.') {
+                            break;
+                        }
+                        number.push(c);
+                        self.bump();
+                    }
+                    tokens.push((line, Token::Number(number)));
+                }
+                c => {
+                    self.bump();
+                    tokens.push((line, Token::Punct(c)));
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// Positional values of the table starting at `tokens[start]` (a `{`), and
+/// the index just past its closing `}`. Keyed fields and nested tables are
+/// skipped but still take no position, like in Lua.
+fn parse_table(tokens: &[(usize, Token)], start: usize) -> Result<(Vec<Option<String>>, usize), LuaError> {
+    let mut values = Vec::new();
+    let mut i = start + 1;
+
+    loop {
+        let line = tokens.get(i).map(|(l, _)| *l).unwrap_or(0);
+        match tokens.get(i).map(|(_, t)| t) {
+            None => return Err(LuaError { line, message: "unterminated table".to_string() }),
+            Some(Token::Punct('}')) => return Ok((values, i + 1)),
+            Some(Token::Punct(',')) | Some(Token::Punct(';')) => i += 1,
+            Some(Token::Punct('{')) => {
+                let (_, next) = parse_table(tokens, i)?;
+                values.push(None);
+                i = next;
+            }
+            // `[key] = value`
+            Some(Token::Punct('[')) => {
+                while !matches!(tokens.get(i).map(|(_, t)| t), Some(Token::Punct('=')) | None) {
+                    i += 1;
+                }
+                i = skip_value(tokens, i + 1)?;
+            }
+            // `key = value`
+            Some(Token::Ident(_)) if matches!(tokens.get(i + 1).map(|(_, t)| t), Some(Token::Punct('='))) => {
+                i = skip_value(tokens, i + 2)?;
+            }
+            Some(Token::Str(s)) => {
+                values.push(Some(s.clone()));
+                i += 1;
+            }
+            Some(_) => {
+                values.push(None);
+                i += 1;
+            }
+        }
+    }
+}
+
+fn skip_value(tokens: &[(usize, Token)], i: usize) -> Result<usize, LuaError> {
+    match tokens.get(i).map(|(_, t)| t) {
+        Some(Token::Punct('{')) => parse_table(tokens, i).map(|(_, next)| next),
+        Some(_) => Ok(i + 1),
+        None => Err(LuaError { line: 0, message: "missing value".to_string() }),
+    }
+}
+
+/// Every `x["@Name"] = { ... }` assignment in a LibCustomNames file. Anything
+/// else in the file is ignored.
+pub fn parse_names(src: &str) -> Result<Vec<HodorPlayer>, LuaError> {
+    let tokens = Lexer::new(src).tokens()?;
+    let mut players = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let window: Vec<&Token> = tokens[i..].iter().take(6).map(|(_, t)| t).collect();
+        if let [Token::Ident(_), Token::Punct('['), Token::Str(key), Token::Punct(']'), Token::Punct('='), Token::Punct('{')] = window.as_slice() {
+            let (values, next) = parse_table(&tokens, i + 5)?;
+            if let (Some(name), Some(Some(text))) = (key.strip_prefix('@'), values.get(1))
+                && !text.is_empty()
+            {
+                players.push(HodorPlayer { name: name.to_string(), text: text.clone() });
+            }
+            i = next;
+        } else {
+            i += 1;
+        }
+    }
+
+    Ok(players)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: &str, text: &str) -> HodorPlayer {
+        HodorPlayer { name: name.to_string(), text: text.to_string() }
+    }
+
+    #[test]
+    fn reads_quoted_strings_and_escapes() {
+        let src = r#"
+            local n = LCN.names
+            n["@One"] = {"One", "|cFFD700\"Quoted\" One|r"}
+            n['@Two'] = {'Two', 'It\'s Two\tTabbed'}
+            n["@Three"] = {"Three", "\x54hree\z
+                  Spaced"}
+        "#;
+
+        assert_eq!(
+            parse_names(src).unwrap(),
+            vec![
+                player("One", "|cFFD700\"Quoted\" One|r"),
+                player("Two", "It's Two\tTabbed"),
+                player("Three", "ThreeSpaced"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_long_brackets() {
+        let src = r#"
+            n["@One"] = {"One", [[Long "One"]]}
+            n["@Two"] = {"Two", [==[Two ]] still ]=] going]==]}
+        "#;
+
+        assert_eq!(
+            parse_names(src).unwrap(),
+            vec![player("One", "Long \"One\""), player("Two", "Two ]] still ]=] going")]
+        );
+    }
+
+    #[test]
+    fn skips_comments() {
+        let src = r#"
+            -- n["@Line"] = {"Line", "commented out"}
+            --[[ n["@Block"] = {"Block", "commented out"} ]]
+            --[==[ n["@Level"] = {"Level", "]] still commented"} ]==]
+            n["@Kept"] = {"Kept", "Kept Name"} -- trailing comment
+        "#;
+
+        assert_eq!(parse_names(src).unwrap(), vec![player("Kept", "Kept Name")]);
+    }
+
+    #[test]
+    fn ignores_extra_table_fields() {
+        let src = r#"
+            n["@One"] = {"One", "First", colour = "FFD700", ["icon"] = "x.dds", {1, 2}, "Third"}
+            n["@Two"] = {icon = {path = "y.dds"}, "Two", "Second"}
+            n["@Three"] = {"Three"}
+            n["@Four"] = {"Four", ""}
+            n["NoAt"] = {"NoAt", "Not a player"}
+        "#;
+
+        assert_eq!(parse_names(src).unwrap(), vec![player("One", "First"), player("Two", "Second")]);
+    }
+
+    #[test]
+    fn decodes_multibyte_names() {
+        let src = "n[\"@Zoë\"] = {\"Zoë\", \"Zoë the Brave\"}\nn[\"@Jose\"] = {\"Jose\", \"Jos\\195\\169 \\xC3\\xA9\"}";
+
+        assert_eq!(parse_names(src).unwrap(), vec![player("Zoë", "Zoë the Brave"), player("Jose", "José é")]);
+    }
+
+    #[test]
+    fn reports_errors_with_lines() {
+        let unterminated = "n[\"@One\"] = {\"One\",\n\"Unterminated}";
+        assert_eq!(parse_names(unterminated).unwrap_err().line, 2);
+
+        let invalid_utf8 = "\n\nn[\"@One\"] = {\"One\", \"\\195\"}";
+        let error = parse_names(invalid_utf8).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.message, "string is not valid UTF-8");
+    }
+}
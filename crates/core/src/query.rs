@@ -0,0 +1,316 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{data::{Class, MasterTable, TableRow, boss_to_boss_name, canonical_set_ids, partition_to_name}, filter::RowFilter};
+
+/// What each row is counted under. Skills and sets count a row once per
+/// distinct skill or set, the rest once per row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupBy {
+    Skill,
+    /// skills counted once per slot, so a skill on both bars counts twice and
+    /// `Measure::Share` can go past 100
+    SkillSlot,
+    /// Perfected and non-Perfected versions count as one set
+    Set,
+    /// main class, see `TableRow::main_class`
+    Class,
+    Boss,
+    Partition,
+    Player,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Measure {
+    /// rows in the group
+    Count,
+    /// percent of the rows that have any group, e.g. rows with any known set
+    Share,
+    /// rows in the group, each partition weighted down to the size of the
+    /// smallest one so long patches don't drown out short ones
+    NormalisedCount,
+    AverageDps,
+    /// lower is better, so these sort lowest first
+    AverageRank,
+}
+
+impl Measure {
+    /// Whether values can be summed into an "Other" group.
+    fn is_additive(&self) -> bool {
+        matches!(self, Measure::Count | Measure::NormalisedCount)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GroupKey {
+    Skill(u16),
+    Set(u16),
+    Class(Class),
+    Boss(u8),
+    Partition(u8),
+    Player(u32),
+    /// everything past `Query::limit`
+    Other,
+}
+
+impl GroupKey {
+    pub fn label(&self, master: &MasterTable) -> String {
+        match *self {
+            GroupKey::Skill(id) => match master.skill(id) {
+                Some(skill) => skill.display_name.clone().unwrap_or_else(|| skill.name.clone()),
+                None => format!("Unknown ({})", id),
+            },
+            GroupKey::Set(id) => match master.set(id) {
+                Some(set) => set.name.clone(),
+                None => format!("Unknown ({})", id),
+            },
+            GroupKey::Class(class) => class.name().to_string(),
+            GroupKey::Boss(id) => boss_to_boss_name(id),
+            GroupKey::Partition(id) => partition_to_name(id),
+            GroupKey::Player(id) => master
+                .player(id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| format!("Unknown ({})", id)),
+            GroupKey::Other => "Other".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryRow {
+    pub key: GroupKey,
+    /// rows counted in the group
+    pub rows: u32,
+    pub value: f64,
+}
+
+/// One aggregation over the master table: filter the rows, group them,
+/// measure each group and keep the best.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub group_by: GroupBy,
+    pub measure: Measure,
+    pub filter: RowFilter,
+    /// groups to keep, the rest are summed into `GroupKey::Other` when the
+    /// measure allows it
+    pub limit: Option<usize>,
+    /// groups with fewer rows are left out
+    pub min_rows: u32,
+}
+
+impl Query {
+    pub fn new(group_by: GroupBy, measure: Measure, filter: &RowFilter) -> Query {
+        Query { group_by, measure, filter: filter.clone(), limit: None, min_rows: 0 }
+    }
+
+    /// Results best first, see `Measure` for the order.
+    pub fn run(&self, master: &MasterTable) -> Vec<QueryRow> {
+        let canonical_id = if self.group_by == GroupBy::Set { canonical_set_ids(master) } else { HashMap::new() };
+
+        let mut rows_per_partition: HashMap<u8, u32> = HashMap::new();
+        for row in self.filter.rows(master) {
+            *rows_per_partition.entry(row.partition_id).or_insert(0) += 1;
+        }
+        let smallest_partition = rows_per_partition.values().copied().min().unwrap_or(0) as f64;
+
+        // rows, weighted rows, dps sum, rank sum
+        let mut groups: BTreeMap<GroupKey, (u32, f64, u64, u64)> = BTreeMap::new();
+        let mut rows_with_any: u32 = 0;
+
+        for row in self.filter.rows(master) {
+            let keys = self.keys(row, &canonical_id);
+            if keys.is_empty() {
+                continue;
+            }
+            rows_with_any += 1;
+
+            let weight = smallest_partition / rows_per_partition[&row.partition_id] as f64;
+            for key in keys {
+                let entry = groups.entry(key).or_default();
+                entry.0 += 1;
+                entry.1 += weight;
+                entry.2 += row.dps as u64;
+                entry.3 += row.ranking as u64;
+            }
+        }
+
+        let mut results: Vec<QueryRow> = groups
+            .into_iter()
+            .filter(|(_, (rows, ..))| *rows >= self.min_rows.max(1))
+            .map(|(key, (rows, weighted, dps, rank))| {
+                let value = match self.measure {
+                    Measure::Count => rows as f64,
+                    Measure::Share => rows as f64 * 100.0 / rows_with_any as f64,
+                    Measure::NormalisedCount => weighted,
+                    Measure::AverageDps => dps as f64 / rows as f64,
+                    Measure::AverageRank => rank as f64 / rows as f64,
+                };
+                QueryRow { key, rows, value }
+            })
+            .collect();
+
+        // stable, so ties keep key order
+        if self.measure == Measure::AverageRank {
+            results.sort_by(|a, b| a.value.total_cmp(&b.value));
+        } else {
+            results.sort_by(|a, b| b.value.total_cmp(&a.value));
+        }
+
+        if let Some(limit) = self.limit.filter(|&limit| results.len() > limit) {
+            let rest: Vec<QueryRow> = results.drain(limit..).collect();
+            if self.measure.is_additive() {
+                results.push(QueryRow {
+                    key: GroupKey::Other,
+                    rows: rest.iter().map(|r| r.rows).sum(),
+                    value: rest.iter().map(|r| r.value).sum(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn keys(&self, row: &TableRow, canonical_id: &HashMap<u16, u16>) -> Vec<GroupKey> {
+        match self.group_by {
+            GroupBy::Skill => row.skill_ids().collect::<HashSet<u16>>().into_iter().map(GroupKey::Skill).collect(),
+            GroupBy::SkillSlot => row.skill_ids().map(GroupKey::Skill).collect(),
+            GroupBy::Set => row
+                .set_ids()
+                .filter(|&id| id != 0)
+                .map(|id| canonical_id.get(&id).copied().unwrap_or(id))
+                .collect::<HashSet<u16>>()
+                .into_iter()
+                .map(GroupKey::Set)
+                .collect(),
+            GroupBy::Class => row.main_class().map(GroupKey::Class).into_iter().collect(),
+            GroupBy::Boss => vec![GroupKey::Boss(row.boss_id)],
+            GroupBy::Partition => vec![GroupKey::Partition(row.partition_id)],
+            GroupBy::Player => vec![GroupKey::Player(row.player_id)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::tests::{close, row, table};
+
+    /// Skill 1 is on both bars of the first row, set 2 is the Perfected
+    /// version of set 1, and the last row wears no known set.
+    fn master() -> MasterTable {
+        table(vec![
+            row(27, 1, 100, &[1, 2, 3, 4, 1], &[1, 2, 3]),
+            row(27, 3, 200, &[1, 3], &[3]),
+            row(28, 2, 300, &[4], &[0]),
+        ])
+    }
+
+    fn values(results: &[QueryRow]) -> Vec<(GroupKey, u32, f64)> {
+        results.iter().map(|r| (r.key, r.rows, r.value)).collect()
+    }
+
+    fn assert_results(results: &[QueryRow], expected: &[(GroupKey, u32, f64)]) {
+        assert_eq!(results.len(), expected.len(), "{:?}", values(results));
+        for (result, &(key, rows, value)) in results.iter().zip(expected) {
+            assert_eq!((result.key, result.rows), (key, rows), "{:?}", values(results));
+            assert!(close(result.value, value), "{:?}", values(results));
+        }
+    }
+
+    #[test]
+    fn skills_count_rows_or_slots() {
+        let master = master();
+        let filter = RowFilter::default();
+
+        assert_results(
+            &Query::new(GroupBy::Skill, Measure::Count, &filter).run(&master),
+            &[
+                (GroupKey::Skill(1), 2, 2.0),
+                (GroupKey::Skill(3), 2, 2.0),
+                (GroupKey::Skill(4), 2, 2.0),
+                (GroupKey::Skill(2), 1, 1.0),
+            ],
+        );
+        assert_results(
+            &Query::new(GroupBy::SkillSlot, Measure::Count, &filter).run(&master),
+            &[
+                (GroupKey::Skill(1), 3, 3.0),
+                (GroupKey::Skill(3), 2, 2.0),
+                (GroupKey::Skill(4), 2, 2.0),
+                (GroupKey::Skill(2), 1, 1.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn sets_merge_perfected_and_share_rows_with_any_set() {
+        let results = Query::new(GroupBy::Set, Measure::Share, &RowFilter::default()).run(&master());
+
+        assert_results(&results, &[(GroupKey::Set(3), 2, 100.0), (GroupKey::Set(1), 1, 50.0)]);
+    }
+
+    #[test]
+    fn measures_per_group() {
+        let master = master();
+        let filter = RowFilter::default();
+
+        assert_results(
+            &Query::new(GroupBy::Partition, Measure::AverageDps, &filter).run(&master),
+            &[(GroupKey::Partition(28), 1, 300.0), (GroupKey::Partition(27), 2, 150.0)],
+        );
+        // lowest rank first, ties in key order
+        assert_results(
+            &Query::new(GroupBy::Skill, Measure::AverageRank, &filter).run(&master),
+            &[
+                (GroupKey::Skill(2), 1, 1.0),
+                (GroupKey::Skill(4), 2, 1.5),
+                (GroupKey::Skill(1), 2, 2.0),
+                (GroupKey::Skill(3), 2, 2.0),
+            ],
+        );
+        // partition 27 has two rows and 28 one, so 27's rows count half
+        assert_results(
+            &Query::new(GroupBy::Skill, Measure::NormalisedCount, &filter).run(&master),
+            &[
+                (GroupKey::Skill(4), 2, 1.5),
+                (GroupKey::Skill(1), 2, 1.0),
+                (GroupKey::Skill(3), 2, 1.0),
+                (GroupKey::Skill(2), 1, 0.5),
+            ],
+        );
+        assert_results(
+            &Query::new(GroupBy::Player, Measure::Count, &RowFilter::partitions(&[28])).run(&master),
+            &[(GroupKey::Player(1), 1, 1.0)],
+        );
+    }
+
+    #[test]
+    fn limit_sums_the_rest_into_other() {
+        let master = master();
+        let filter = RowFilter::default();
+
+        let query = Query { limit: Some(2), ..Query::new(GroupBy::SkillSlot, Measure::Count, &filter) };
+        assert_results(
+            &query.run(&master),
+            &[(GroupKey::Skill(1), 3, 3.0), (GroupKey::Skill(3), 2, 2.0), (GroupKey::Other, 3, 3.0)],
+        );
+
+        // shares can't be summed, so the rest is dropped
+        let query = Query { limit: Some(1), ..Query::new(GroupBy::Set, Measure::Share, &filter) };
+        assert_results(&query.run(&master), &[(GroupKey::Set(3), 2, 100.0)]);
+
+        // nothing past the limit, no Other
+        let query = Query { limit: Some(5), ..Query::new(GroupBy::Set, Measure::Count, &filter) };
+        assert_results(&query.run(&master), &[(GroupKey::Set(3), 2, 2.0), (GroupKey::Set(1), 1, 1.0)]);
+    }
+
+    #[test]
+    fn min_rows_leaves_out_small_groups() {
+        let master = master();
+        let query = Query { min_rows: 2, ..Query::new(GroupBy::Skill, Measure::AverageDps, &RowFilter::default()) };
+
+        assert_results(
+            &query.run(&master),
+            &[(GroupKey::Skill(4), 2, 200.0), (GroupKey::Skill(1), 2, 150.0), (GroupKey::Skill(3), 2, 150.0)],
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use yew::{Callback, Html, Properties, function_component, html, use_state};
+
+use parselist::{builds::{Archetype, BuildOptions, archetypes}, filter::RowFilter};
+
+use crate::{data::partition_to_name, filters::FilterControls};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct BuildsPageProps {
+pub master_table: Rc<crate::data::MasterTable>,
+}
+
+fn skill_names(archetype: &Archetype) -> String {
+    archetype
+        .core_skills
+        .iter()
+        .map(|s| s.display_name.clone().unwrap_or_else(|| s.name.clone()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[function_component(BuildsPage)]
+pub fn builds_page(props: &BuildsPageProps) -> Html {
+    let master_table = props.master_table.clone();
+    let filter = use_state(RowFilter::default);
+    let selected = use_state(|| None::<usize>);
+
+    let on_filter = {
+        let filter = filter.clone();
+        let selected = selected.clone();
+        Callback::from(move |f: RowFilter| {
+            selected.set(None);
+            filter.set(f)
+        })
+    };
+
+    let split_skills = use_state(|| false);
+
+    let toggle_split_skills = {
+        let split_skills = split_skills.clone();
+        let selected = selected.clone();
+        Callback::from(move |_| {
+            selected.set(None);
+            split_skills.set(!*split_skills)
+        })
+    };
+
+    let toggle_style = "background-color: #15171fff; font-family: 'TF2Build'; color: #fff; border: none; padding: 0.25em 0.5em; outline: none; font-size: 1.5em; border-radius: 0.25em; cursor: pointer;";
+    let toggle_label = if *split_skills { "Splitting Builds By Skill Bars" } else { "Grouping By Class And Sets Only" };
+
+    let options = BuildOptions {
+        skill_similarity: if *split_skills { Some(0.6) } else { None },
+        min_rows: 10,
+    };
+    let builds: Vec<Archetype> = archetypes(&master_table, &filter, &options).into_iter().take(25).collect();
+
+    let build_rows: Html = builds
+        .iter()
+        .enumerate()
+        .map(|(i, archetype)| {
+            let on_click = {
+                let selected = selected.clone();
+                Callback::from(move |_| selected.set(Some(i)))
+            };
+            let row_style = if *selected == Some(i) { "cursor: pointer; background-color: #15171fff;" } else { "cursor: pointer;" };
+
+            html! {
+                <tr onclick={on_click} style={row_style}>
+                    <td> { archetype.label() } </td>
+                    <td style="font-size: 0.75em;"> { skill_names(archetype) } </td>
+                    <td> { format!("{:.1}%", archetype.share) } </td>
+                    <td> { format!("{:.1}k", archetype.average_dps / 1000.0) } </td>
+                    <td> { format!("{:.1}", archetype.average_rank) } </td>
+                </tr>
+            }
+        })
+        .collect();
+
+    let details: Html = match selected.and_then(|i| builds.get(i)) {
+        Some(archetype) => html! {
+            <table style="margin-left: auto; margin-right: auto; margin-top: 1em; font-size: 1.5em;">
+                <thead style="color: #fff; text-align: center;">
+                    <tr>
+                        <th style="width: 40%">{ archetype.label() }</th>
+                        <th style="width: 20%">{"Parses"}</th>
+                        <th style="width: 20%">{"Avg DPS"}</th>
+                        <th style="width: 20%">{"Avg Rank"}</th>
+                    </tr>
+                </thead>
+                <tbody style="color: #fff; text-align: center;">
+                    { for archetype.partitions.iter().rev().map(|stats|
+                        html! {
+                            <tr>
+                                <td> { partition_to_name(stats.partition_id) } </td>
+                                <td> { format!("{:.1}%", stats.share) } </td>
+                                <td> { format!("{:.1}k", stats.average_dps / 1000.0) } </td>
+                                <td> { format!("{:.1}", stats.average_rank) } </td>
+                            </tr>
+                        }
+                    ) }
+                </tbody>
+            </table>
+        },
+        None => html! {
+            <div style="font-size: 1.5rem; user-select: none;">{"Click a build to see how it did in each patch"}</div>
+        },
+    };
+
+    html! {
+        <div style="width: 100%; display: flex; flex-direction: column; gap: 2rem; align-items: center; color: #fff;">
+            <FilterControls master_table={master_table.clone()} filter={(*filter).clone()} on_change={on_filter} />
+            <button onclick={toggle_split_skills} style={toggle_style}>{toggle_label}</button>
+
+            <div style="font-size: 2rem; margin: 1rem; font-weight: bold; user-select: none; text-align: center;">{"Most Common Builds"}</div>
+            <table style="margin-left: auto; margin-right: auto; font-size: 1.5em;">
+                <thead style="color: #fff; text-align: center;">
+                    <tr>
+                        <th style="width: 35%">{"Build"}</th>
+                        <th style="width: 35%">{"Core Skills"}</th>
+                        <th style="width: 10%">{"Parses"}</th>
+                        <th style="width: 10%">{"Avg DPS"}</th>
+                        <th style="width: 10%">{"Avg Rank"}</th>
+                    </tr>
+                </thead>
+                <tbody style="color: #fff; text-align: center;">
+                    { build_rows }
+                </tbody>
+            </table>
+
+            { details }
+        </div>
+    }
+}
@@ -0,0 +1,274 @@
+use charming::element::Color;
+
+use parselist::{
+    data::{
+        Class, GroupedUsage, ItemSet, MasterTable, Skill, SkillGroup, UsageTarget, percent_players_with_sets, percent_players_with_skills,
+        percent_players_with_skills_grouped, top_n_sets_for_partitions, top_n_sets_for_partitions_normalised, top_n_skills_for_partitions,
+        top_n_skills_for_partitions_grouped, top_n_skills_for_partitions_normalised, top_n_skills_for_partitions_normalised_grouped,
+    },
+    filter::RowFilter,
+};
+
+pub fn colour_from_class(class: Class) -> Color {
+    let hex = match class {
+        Class::Arcanist      => "#9ACD32",
+        Class::Dragonknight  => "#FF8C00",
+        Class::Nightblade    => "#AA0000",
+        Class::Templar       => "#FFD700",
+        Class::Sorcerer      => "#1E90FF",
+        Class::Warden        => "#228B22",
+        Class::Necromancer   => "#8A2BE2",
+    };
+
+    Color::Value(hex.to_string())
+}
+
+pub fn colour_from_skill(skill: &Skill) -> Color {
+    if let Some(class) = skill.class.as_deref().and_then(Class::from_name) {
+        return colour_from_class(class);
+    }
+
+    let hex = match skill.class.as_deref() {
+        Some("Weapon")        => "#FFE4C4",
+
+        Some(_) => match skill.tree.as_deref() {
+            Some("Vampire")        => "#8B0000",
+            Some("Fighters Guild") => "#B73700",
+            Some("Mages Guild")    => "#4682B4",
+            Some("Psijic Order")   => "#008B8B",
+            Some("Undaunted")      => "#70B04A",
+            Some("Assault")        => "#FA8072",
+            Some("Support")        => "#87CEFA",
+            Some("Soul Magic")     => "#800080",
+            _ => "#B2B2B2",
+        },
+
+        None => "#B2B2B2",
+    };
+
+    Color::Value(hex.to_string())
+}
+
+pub fn top_n_skills_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(i32, String)>, Vec<Color>) {
+    let top_skills = top_n_skills_for_partitions(master, filter, n);
+
+    let mut data: Vec<(i32, String)> = Vec::with_capacity(top_skills.len());
+    let mut colours: Vec<Color> = Vec::with_capacity(top_skills.len());
+
+    for (skill, count) in top_skills {
+        let name = skill.display_name.clone().unwrap_or_else(|| skill.name.clone());
+        data.push((count as i32, name));
+        colours.push(colour_from_skill(&skill));
+    }
+
+    (data, colours)
+}
+
+pub fn top_n_skills_normalised_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(i32, String)>, Vec<Color>) {
+    let top_skills = top_n_skills_for_partitions_normalised(master, filter, n);
+
+    let mut data: Vec<(i32, String)> = Vec::with_capacity(top_skills.len());
+    let mut colours: Vec<Color> = Vec::with_capacity(top_skills.len());
+
+    for (skill, count) in top_skills {
+        let name = skill.display_name.clone().unwrap_or_else(|| skill.name.clone());
+        data.push((count as i32, name));
+        colours.push(colour_from_skill(&skill));
+    }
+
+    (data, colours)
+}
+
+pub fn colour_from_skill_group(group: &SkillGroup) -> Color {
+    match group.morphs.first() {
+        Some(skill) => colour_from_skill(skill),
+        None => Color::Value("#B2B2B2".to_string()),
+    }
+}
+
+pub fn top_n_skill_groups_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(i32, String)>, Vec<Color>) {
+    skill_group_chart_vectors(top_n_skills_for_partitions_grouped(master, filter, n))
+}
+
+pub fn top_n_skill_groups_normalised_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(i32, String)>, Vec<Color>) {
+    skill_group_chart_vectors(top_n_skills_for_partitions_normalised_grouped(master, filter, n))
+}
+
+fn skill_group_chart_vectors(top_groups: Vec<GroupedUsage<u32>>) -> (Vec<(i32, String)>, Vec<Color>) {
+    let mut data: Vec<(i32, String)> = Vec::with_capacity(top_groups.len());
+    let mut colours: Vec<Color> = Vec::with_capacity(top_groups.len());
+
+    for (group, count, _) in top_groups {
+        data.push((count as i32, group.name.clone()));
+        colours.push(colour_from_skill_group(&group));
+    }
+
+    (data, colours)
+}
+
+pub fn colour_from_set(set: &ItemSet) -> Color {
+    let hex = match set.id {
+        83 => "#CF6A32", // Elf Bane
+        127 => "#476291", // Deadly Strike
+        137 => "#D32CE6", // Berserking Warrior (Advancing Yokeda)
+        // 147 => "#38F3AB", // Way of Martial Knowledge
+        // 205 => "#4B69FF", // Willpower
+        // 232 => "#38F3AB", // Roar of Alkosh
+        292 => "#8650AC", // Mother's Sorrow
+        304 => "#70B04A", // Medusa
+        // 332 => "#AA0000", // Master Architect
+        336 => "#A32C2E", // Pillar of Nirn
+        338 => "#CF6A32", // Flame Blossom
+        353 => "#4B69FF", // Mechanical Acuity
+        389 | 393 => "#FFD700", // Arms of Relequen
+        390 | 394 => "#F4A460", // Mantle of Siroria
+        430 => "#DAA520", // Tzogvin's Warband
+        444 | 449 => "#00BFFF", // False God's Devotion
+        445 | 450 => "#B22222", // Tooth of Lokkestiiz
+        455 => "#6B8E23", // Z'en's Redress
+        456 => "#007FFF", // Azureblight Reaper
+        470 => "#476291", // New Moon Acolyte
+        475 => "#AA0000", // Aegis Caller
+        570 => "#38F3AB", // Kinras's Wrath
+        584 => "#48D1CC", // Diamond's Victory
+        586 | 589 => "#70B04A", // Sul-Xan's Torment
+        587 | 591 => "#50A7FC", // Bahsei's Mania
+        646 | 653 => "#4B69FF", // Whorl of the Depths
+        647 | 652 => "#96DA43", // Coral Riptide
+        684 => "#FF4500", // Runecarver's Blaze
+        702 | 707 => "#2F4F4F", // Ansuul's Torment
+        764 => "#F4A460", // Highland Sentinel
+        767 | 772 => "#E4AE33", // Slivers of the Null Arca
+        777 => "#8847FF", // Corpseburster
+        809 => "#8FBC8F", // Tide-Born Wildstalker
+        168 | // Nerien'eth
+        169 | // Valkyn Skoria
+        170 | // Maw of the Infernal
+        257 | // Velidreth
+        273 | // Ilambris
+        274 | // Iceheart
+        275 | // Stormfist
+        279 | // Selene
+        280 | // Grothdarr
+        342 | // Domihaus
+        350 | // Zaan
+        458 | // Grundwulf
+        459 // Maarselok
+        => "#B0C4DE", // Monster Sets
+        270 => "#4D7942", // Slimecraw
+        373 | 526 => "#99CCFF", // Crushing Wall
+        369 | 522 => "#FFC0CB", // Merciless Charge
+        372 | 525 // Thunderous Volley
+        | 367 | 361 // Concentrated Force
+        | 316 | 531 // Caustic Arrow
+        | 413 | 425 // Spectral Cloak
+        | 371 | 524 // Cruel Flurry
+        => "#FFE4C4", // Arena Weapons
+        501 | 503 | 505 | 519 | 520 | 521 | 575 | 576 | 593 | 594 | 596 | 597 | 625 | 626 | 627 | 654 | 655 | 656 | 657 | 658 | 674 | 675 | 676 | 691 | 692 | 693 | 694 | 760 | 761 | 762 | 811 | 812 | 813 | 845 => "#FF8200", // Mythics
+        999 => "#B2B2B2",
+        _ => "#B2B2B2",
+    };
+
+    Color::Value(hex.to_string())
+}
+
+pub fn top_n_sets_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(i32, String)>, Vec<Color>) {
+    let top_sets = top_n_sets_for_partitions(master, filter, n);
+
+    let mut data: Vec<(i32, String)> = Vec::with_capacity(top_sets.len());
+    let mut colours: Vec<Color> = Vec::with_capacity(top_sets.len());
+
+    for (set, count) in top_sets {
+        let name = set.name.clone();
+        data.push((count as i32, name));
+        colours.push(colour_from_set(&set));
+    }
+
+    (data, colours)
+}
+
+pub fn top_n_sets_normalised_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(i32, String)>, Vec<Color>) {
+    let top_sets = top_n_sets_for_partitions_normalised(master, filter, n);
+
+    let mut data: Vec<(i32, String)> = Vec::with_capacity(top_sets.len());
+    let mut colours: Vec<Color> = Vec::with_capacity(top_sets.len());
+
+    for (set, count) in top_sets {
+        let name = set.name.clone();
+        data.push((count as i32, name));
+        colours.push(colour_from_set(&set));
+    }
+
+    (data, colours)
+}
+
+pub fn top_n_sets_percentage_chart_vectors(
+    master: &MasterTable,
+    filter: &RowFilter,
+    n: usize,
+) -> (Vec<(f64, String)>, Vec<Color>) {
+    let top_sets = percent_players_with_sets(master, filter);
+
+    let mut data: Vec<(f64, String)> = Vec::new();
+    let mut colours: Vec<Color> = Vec::new();
+
+    for (set, pct) in top_sets.into_iter().take(n) {
+        data.push((pct, set.name.clone()));
+        colours.push(colour_from_set(&set));
+    }
+
+    (data, colours)
+}
+
+pub fn top_n_skills_percentage_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> (Vec<(f64, String)>, Vec<Color>) {
+    let top_sets = percent_players_with_skills(master, filter);
+
+    let mut data: Vec<(f64, String)> = Vec::new();
+    let mut colours: Vec<Color> = Vec::new();
+
+    for (skill, pct) in top_sets.into_iter().take(n) {
+        data.push((pct, skill.display_name.clone().unwrap_or_else(|| skill.name.clone())));
+        colours.push(colour_from_skill(&skill));
+    }
+
+    (data, colours)
+}
+
+/// Label of a stacked bar and the value, name and colour of each segment.
+pub type StackedBar = (String, Vec<(f64, String, Color)>);
+
+/// Top `n` base skills by share of parses, each with the share of every
+/// morph for a stacked bar.
+pub fn top_n_skill_groups_percentage_chart_vectors(master: &MasterTable, filter: &RowFilter, n: usize) -> Vec<StackedBar> {
+    percent_players_with_skills_grouped(master, filter)
+        .into_iter()
+        .take(n)
+        .map(|(group, _, morphs)| {
+            let morphs = morphs
+                .iter()
+                .map(|(skill, pct)| (*pct, skill.display_name.clone().unwrap_or_else(|| skill.name.clone()), colour_from_skill(skill)))
+                .collect();
+            (group.name, morphs)
+        })
+        .collect()
+}
+
+/// Chart label and colour for a set or skill.
+pub fn usage_target_label(master: &MasterTable, target: UsageTarget) -> (String, Color) {
+    match target {
+        UsageTarget::Set(id) => {
+            let set = master.set(id).cloned().unwrap_or(ItemSet {
+                id,
+                name: format!("Unknown ({})", id),
+            });
+            (set.name.clone(), colour_from_set(&set))
+        }
+        UsageTarget::Skill(id) => match master.skill(id) {
+            Some(skill) => (
+                skill.display_name.clone().unwrap_or_else(|| skill.name.clone()),
+                colour_from_skill(skill),
+            ),
+            None => (format!("Unknown ({})", id), Color::Value("#B2B2B2".to_string())),
+        },
+    }
+}
@@ -0,0 +1,60 @@
+use std::rc::Rc;
+
+use yew::{Html, Properties, function_component, html};
+
+use crate::graph::ClassShareBarChart;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ClassesPageProps {
+pub master_table: Rc<crate::data::MasterTable>,
+}
+
+
+#[function_component(ClassesPage)]
+pub fn classes_page(props: &ClassesPageProps) -> Html {
+    let master_table = props.master_table.clone();
+
+    html! {
+        <div style="width: 100%; display: flex; flex-direction: column; gap: 2rem; align-items: center; color: #fff;">
+            <ClassShareBarChart
+                master_table={master_table.clone()}
+                partitions={vec![]}
+                top_n={7}
+                chart_id={"classes".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <ClassShareBarChart
+                master_table={master_table.clone()}
+                partitions={vec![27]}
+                top_n={7}
+                by_boss={true}
+                chart_id={"classes_bosses".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <ClassShareBarChart
+                master_table={master_table.clone()}
+                partitions={vec![]}
+                top_n={10}
+                combos={true}
+                chart_id={"classcombos".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <ClassShareBarChart
+                master_table={master_table.clone()}
+                partitions={vec![27]}
+                top_n={10}
+                by_boss={true}
+                combos={true}
+                chart_id={"classcombos_bosses".to_string()}
+                width={1500}
+                height={700}
+            />
+        </div>
+    }
+}
@@ -0,0 +1,190 @@
+use std::rc::Rc;
+
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{Callback, Html, Properties, events::{Event, TargetCast}, function_component, html};
+
+use crate::data::{UsageTarget, boss_to_boss_name, boss_to_zone, zone_to_zone_name};
+use parselist::{dates::{Date, DateRange}, filter::{DpsType, RowFilter}};
+
+const RANKINGS: [(&str, Option<(u8, u8)>); 5] = [
+    ("All Rankings", None),
+    ("Top 10", Some((1, 10))),
+    ("Top 25", Some((1, 25))),
+    ("Top 50", Some((1, 50))),
+    ("Ranks 51-100", Some((51, 100))),
+];
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct FilterControlsProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    pub filter: RowFilter,
+    pub on_change: Callback<RowFilter>,
+}
+
+fn select_value(e: &Event) -> Option<String> {
+    e.target_dyn_into::<HtmlSelectElement>().map(|select| select.value())
+}
+
+/// The day picked in a date input, `None` when it's cleared.
+fn date_value(e: &Event) -> Option<Date> {
+    e.target_dyn_into::<HtmlInputElement>().and_then(|input| input.value().parse().ok())
+}
+
+#[function_component(FilterControls)]
+pub fn filter_controls(props: &FilterControlsProps) -> Html {
+    let filter = props.filter.clone();
+
+    let mut zones: Vec<u8> = props.master_table.boss_ids().map(boss_to_zone).collect();
+    zones.sort();
+    zones.dedup();
+
+    // only the bosses of the chosen trial, or every boss when none is chosen
+    let bosses: Vec<u8> = props
+        .master_table
+        .boss_ids()
+        .filter(|&b| filter.zones.is_empty() || filter.zones.contains(&boss_to_zone(b)))
+        .collect();
+
+    let on_zone = {
+        let filter = filter.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let zones = select_value(&e).and_then(|v| v.parse().ok()).into_iter().collect();
+            on_change.emit(RowFilter { zones, bosses: vec![], ..filter.clone() });
+        })
+    };
+
+    let on_boss = {
+        let filter = filter.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let bosses = select_value(&e).and_then(|v| v.parse().ok()).into_iter().collect();
+            on_change.emit(RowFilter { bosses, ..filter.clone() });
+        })
+    };
+
+    let on_dps_type = {
+        let filter = filter.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let dps_type = select_value(&e)
+                .and_then(|v| DpsType::ALL.into_iter().find(|t| t.name() == v))
+                .unwrap_or_default();
+            on_change.emit(RowFilter { dps_type, ..filter.clone() });
+        })
+    };
+
+    let on_rankings = {
+        let filter = filter.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let rankings = select_value(&e)
+                .and_then(|v| RANKINGS.iter().find(|(label, _)| *label == v))
+                .and_then(|(_, range)| *range);
+            on_change.emit(RowFilter { rankings, ..filter.clone() });
+        })
+    };
+
+    let on_from = {
+        let filter = filter.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let dates = DateRange { start: date_value(&e), ..filter.dates };
+            on_change.emit(RowFilter { dates, ..filter.clone() });
+        })
+    };
+
+    let on_to = {
+        let filter = filter.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let dates = DateRange { end: date_value(&e), ..filter.dates };
+            on_change.emit(RowFilter { dates, ..filter.clone() });
+        })
+    };
+
+    let select_style = "background-color: #15171fff; font-family: 'TF2Build'; color: #fff; border: none; padding: 0.25em 0.5em; outline: none; font-size: 1.25em; border-radius: 0.25em; cursor: pointer;";
+
+    html! {
+        <div style="display: flex; flex-wrap: wrap; gap: 1rem; justify-content: center; user-select: none;">
+            <select onchange={on_zone} style={select_style}>
+                <option value="" selected={filter.zones.is_empty()}>{"All Trials"}</option>
+                { for zones.iter().map(|&z| html! {
+                    <option value={z.to_string()} selected={filter.zones.contains(&z)}>{zone_to_zone_name(z)}</option>
+                }) }
+            </select>
+            <select onchange={on_boss} style={select_style}>
+                <option value="" selected={filter.bosses.is_empty()}>{"All Bosses"}</option>
+                { for bosses.iter().map(|&b| html! {
+                    <option value={b.to_string()} selected={filter.bosses.contains(&b)}>{boss_to_boss_name(b)}</option>
+                }) }
+            </select>
+            <select onchange={on_dps_type} style={select_style}>
+                { for DpsType::ALL.iter().map(|t| html! {
+                    <option value={t.name()} selected={filter.dps_type == *t}>{t.name()}</option>
+                }) }
+            </select>
+            <select onchange={on_rankings} style={select_style}>
+                { for RANKINGS.iter().map(|(label, range)| html! {
+                    <option value={*label} selected={filter.rankings == *range}>{*label}</option>
+                }) }
+            </select>
+            <input type="date" title="From" onchange={on_from} style={select_style}
+                value={filter.dates.start.map(|d| d.to_string()).unwrap_or_default()} />
+            <input type="date" title="To" onchange={on_to} style={select_style}
+                value={filter.dates.end.map(|d| d.to_string()).unwrap_or_default()} />
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct UsagePickerProps {
+    /// every target that can be picked, with its label, in menu order
+    pub options: Vec<(UsageTarget, String)>,
+    pub selected: Vec<UsageTarget>,
+    pub placeholder: String,
+    pub on_change: Callback<Vec<UsageTarget>>,
+}
+
+/// A menu to add sets or skills to a list, and a button to remove each one.
+#[function_component(UsagePicker)]
+pub fn usage_picker(props: &UsagePickerProps) -> Html {
+    let on_add = {
+        let options = props.options.clone();
+        let selected = props.selected.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let picked = select_value(&e).and_then(|v| v.parse::<usize>().ok()).and_then(|i| options.get(i));
+            if let Some((target, _)) = picked {
+                let mut selected = selected.clone();
+                selected.push(*target);
+                on_change.emit(selected);
+            }
+        })
+    };
+
+    let select_style = "background-color: #15171fff; font-family: 'TF2Build'; color: #fff; border: none; padding: 0.25em 0.5em; outline: none; font-size: 1.25em; border-radius: 0.25em; cursor: pointer;";
+
+    html! {
+        <div style="display: flex; flex-wrap: wrap; gap: 1rem; justify-content: center; align-items: center; user-select: none;">
+            <select onchange={on_add} style={select_style}>
+                <option value="" selected={true}>{props.placeholder.clone()}</option>
+                { for props.options.iter().enumerate().filter(|(_, (t, _))| !props.selected.contains(t)).map(|(i, (_, label))| html! {
+                    <option value={i.to_string()}>{label.clone()}</option>
+                }) }
+            </select>
+            { for props.selected.iter().map(|target| {
+                let label = props.options.iter().find(|(t, _)| t == target).map(|(_, l)| l.clone()).unwrap_or_default();
+                let on_remove = {
+                    let target = *target;
+                    let selected = props.selected.clone();
+                    let on_change = props.on_change.clone();
+                    Callback::from(move |_| on_change.emit(selected.iter().copied().filter(|t| *t != target).collect()))
+                };
+                html! {
+                    <button onclick={on_remove} style={select_style}>{format!("{} \u{2715}", label)}</button>
+                }
+            }) }
+        </div>
+    }
+}
@@ -0,0 +1,1173 @@
+use std::rc::Rc;
+use charming::{
+    Chart, Echarts, WasmRenderer, component::{Axis, Grid, Legend, LegendType, VisualMap}, datatype::{DataFrame, DataPoint, DataPointItem}, element::{AxisLabel, AxisType, Color, ItemStyle, JsFunction, Label, LabelAlign, LabelLine, LabelPosition, LineStyle, NameLocation, Orient, TextStyle, Tooltip, Trigger}, series::{Bar, Heatmap, Line, Pie}
+};
+use wasm_bindgen::{JsCast, JsValue, prelude::{Closure, wasm_bindgen}};
+use yew::prelude::*;
+
+use crate::{chart_data::{StackedBar, colour_from_class}, data::{Class, Player, SetCombo, SkillCompanion, UsageTarget, boss_to_boss_name, class_combo_label, partition_to_name, partition_to_update_id, set_combo_label}, players::hodor_name_to_html};
+use parselist::filter::RowFilter;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The echarts instance behind charming's `Echarts`, for the event
+    /// methods charming doesn't wrap.
+    type EchartsEvents;
+
+    #[wasm_bindgen(method, js_name = "on")]
+    fn on(this: &EchartsEvents, event: &str, handler: &js_sys::Function);
+}
+
+/// Calls `handler` with the `dataIndex` of every clicked item. The closure is
+/// leaked, as it has to live as long as the chart.
+fn on_click(echarts: &Echarts, handler: impl Fn(usize) + 'static) {
+    let closure = Closure::<dyn Fn(JsValue)>::new(move |params: JsValue| {
+        let index = js_sys::Reflect::get(&params, &JsValue::from_str("dataIndex")).ok().and_then(|v| v.as_f64());
+        if let Some(index) = index {
+            handler(index as usize);
+        }
+    });
+    echarts.unchecked_ref::<EchartsEvents>().on("click", closure.as_ref().unchecked_ref());
+    closure.forget();
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PieChartProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    pub partitions: Vec<u8>,
+    /// bosses, trials, ST/AOE and rankings to count, over `partitions`
+    #[prop_or_default]
+    pub filter: RowFilter,
+    pub top_n: usize,
+    pub chart_id: String,
+    pub width: u32,
+    pub height: u32,
+    /// count morphs under their base skill, skill charts only
+    #[prop_or_default]
+    pub group_morphs: bool,
+    /// called with the skill id when a bar is clicked, ungrouped skill bar
+    /// graphs only
+    #[prop_or_default]
+    pub on_select: Option<Callback<u16>>,
+}
+
+fn compute_title(partitions: &[u8], default_all_title: &str) -> String {
+    if partitions.len() == 1 {
+        partition_to_name(*partitions.first().unwrap())
+    } else if partitions.len() > 1 && partitions.len() < 27 {
+        format!(
+            "Update {} ->  Update {}",
+            partition_to_update_id(*partitions.first().unwrap()),
+            partition_to_update_id(*partitions.last().unwrap())
+        )
+    } else {
+        default_all_title.to_string()
+    }
+}
+
+fn default_tooltip() -> Tooltip {
+    Tooltip::new()
+        .trigger(Trigger::Item)
+        .background_color("#282c38".to_string())
+        .border_width(2)
+        .formatter(JsFunction::new_with_args(
+            "params",
+            r#"
+                return `
+                <div style="color: #fff; font-family: "TF2Build";">${params.dataIndex+1}. ${params.name}: ${params.percent}%</div>
+                `
+            "#,
+        ))
+}
+
+fn percent_tooltip() -> Tooltip {
+    Tooltip::new()
+        .trigger(Trigger::Item)
+        .background_color("#282c38".to_string())
+        .border_width(2)
+        .formatter(JsFunction::new_with_args(
+            "params",
+            r#"
+                return `
+                <div style="color: #fff; font-family: "TF2Build";">
+                    ${params.dataIndex + 1}. ${params.name}: ${params.value.toFixed(1)}%
+                </div>
+                `
+            "#,
+        ))
+}
+
+fn default_legend() -> Legend {
+    Legend::new()
+        .left("58%")
+        .width("20%")
+        .top("center")
+        .height("100%")
+        .type_(LegendType::Scroll)
+        .orient(Orient::Vertical)
+        .text_style(
+            TextStyle::new()
+                .color(Color::Value("#FFFFFF".to_string()))
+                .font_family("TF2Build")
+                .font_size(15),
+        )
+        .item_gap(3)
+}
+
+fn default_pie_base() -> Pie {
+    Pie::new()
+        .center(vec!["28%", "50%"])
+        .radius(vec!["25%", "90%"])
+        .avoid_label_overlap(true)
+        .label(Label::new().show(false).color(Color::Value("#FFFFFF".to_string())))
+        .label_line(LabelLine::new().show(false))
+        .item_style(ItemStyle::new().border_color("#282c38").border_width(4).border_radius(8))
+}
+
+fn build_chart_from_data(chart_data: Vec<(i32, String)>, chart_colors: Vec<Color>) -> Chart {
+    let pie = default_pie_base().data(chart_data);
+    Chart::new()
+        .tooltip(default_tooltip())
+        .legend(default_legend())
+        .color(chart_colors)
+        .series(pie)
+}
+
+fn large_tooltip() -> Tooltip {
+    default_tooltip()
+}
+
+#[allow(dead_code)]
+fn large_legend() -> Legend {
+    Legend::new()
+        .left("90%")
+        .width("10%")
+        .top("center")
+        .height("90%")
+        .type_(LegendType::Scroll)
+        .orient(Orient::Vertical)
+        .text_style(
+            TextStyle::new()
+                .color(Color::Value("#FFFFFF".to_string()))
+                .font_family("TF2Build")
+                .font_size(18),
+        )
+        .item_gap(4)
+}
+
+fn large_pie_base() -> Pie {
+    Pie::new()
+        .center(vec!["50%", "50%"])
+        .radius(vec!["25%", "95%"])
+        .avoid_label_overlap(true)
+        .label(Label::new().show(true).color(Color::Value("#FFFFFF".to_string())).font_family("TF2Build").distance(10).font_size(16))
+        .label_line(LabelLine::new().show(true).show_above(true).length(40).line_style(LineStyle::new().width(2.5)))
+        .item_style(ItemStyle::new().border_color("#282c38").border_width(4).border_radius(16))
+}
+
+fn build_large_chart_from_data(chart_data: Vec<(i32, String)>, chart_colors: Vec<Color>) -> Chart {
+    let pie = large_pie_base().data(chart_data);
+    Chart::new()
+        .tooltip(large_tooltip())
+        // .legend(large_legend())
+        .color(chart_colors)
+        .series(pie)
+}
+
+pub fn build_large_bar_graph_from_data(chart_data: Vec<(f64, String)>, chart_colors: Vec<Color>) -> Chart {
+    let labels: Vec<String> = chart_data.iter().map(|(_, lbl)| lbl.clone()).collect();
+
+    let data_points: Vec<DataPointItem> = chart_data
+        .iter()
+        .enumerate()
+        .map(|(idx, (val, _))| {
+            let color = chart_colors.get(idx).cloned().unwrap_or(Color::from("#000000"));
+            DataPointItem::new(*val).item_style(ItemStyle::new().color(color))
+        })
+        .collect();
+
+    Chart::new()
+        .x_axis(Axis::new().data(labels).name("Set Name").name_location(NameLocation::Center).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).axis_label(AxisLabel::new().show(false)))
+        .y_axis(Axis::new().name("Percent of Parses Using The Set").name_location(NameLocation::Center).name_gap(45).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).max_interval(10).max(100).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16)))
+        .series(Bar::new().data(data_points).label(Label::new().show(true).position(LabelPosition::Top).align(LabelAlign::Left).color("#fff").font_family("TF2Build").font_size(16).formatter("  {b}").rotate("35").offset((-10, 0))))
+        .color(chart_colors)
+        .tooltip(percent_tooltip())
+        .legend(Legend::new().show(false))
+}
+
+pub fn build_large_bar_graph_skills_from_data(chart_data: Vec<(f64, String)>, chart_colors: Vec<Color>) -> Chart {
+    let labels: Vec<String> = chart_data.iter().map(|(_, lbl)| lbl.clone()).collect();
+
+    let data_points: Vec<DataPointItem> = chart_data
+        .iter()
+        .enumerate()
+        .map(|(idx, (val, _))| {
+            let color = chart_colors.get(idx).cloned().unwrap_or(Color::from("#000000"));
+            DataPointItem::new(*val).item_style(ItemStyle::new().color(color))
+        })
+        .collect();
+
+    Chart::new()
+        .x_axis(Axis::new().data(labels).name("Skill Name").name_location(NameLocation::Center).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).axis_label(AxisLabel::new().show(false)))
+        .y_axis(Axis::new().name("Percent of Parses Using The Skill").name_location(NameLocation::Center).name_gap(45).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).max_interval(10).max(100).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16)))
+        .series(Bar::new().data(data_points).label(Label::new().show(true).position(LabelPosition::Top).align(LabelAlign::Left).color("#fff").font_family("TF2Build").font_size(16).formatter("  {b}").rotate("35").offset((-10, 0))))
+        .color(chart_colors)
+        .tooltip(percent_tooltip())
+        .legend(Legend::new().show(false))
+}
+
+/// One bar per base skill, stacked by morph. Series `i` holds the `i`th most
+/// used morph of every bar.
+pub fn build_large_bar_graph_skill_groups_from_data(bars: Vec<StackedBar>) -> Chart {
+    let labels: Vec<String> = bars.iter().map(|(lbl, _)| lbl.clone()).collect();
+    let depth = bars.iter().map(|(_, morphs)| morphs.len()).max().unwrap_or(0);
+
+    let mut chart = Chart::new()
+        .x_axis(Axis::new().data(labels).name("Base Skill").name_location(NameLocation::Center).name_gap(140).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(14).rotate(35)))
+        .y_axis(Axis::new().name("Percent of Parses Using The Skill").name_location(NameLocation::Center).name_gap(45).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).max_interval(10).max(100).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16)))
+        .tooltip(percent_tooltip())
+        .legend(Legend::new().show(false));
+
+    for i in 0..depth {
+        let data_points: Vec<DataPointItem> = bars
+            .iter()
+            .map(|(_, morphs)| match morphs.get(i) {
+                Some((val, name, color)) => DataPointItem::new(*val)
+                    .name(name.clone())
+                    .item_style(ItemStyle::new().color(color.clone()).border_color("#282c38").border_width(2)),
+                None => DataPointItem::new(0.0),
+            })
+            .collect();
+
+        chart = chart.series(Bar::new().stack("morphs").data(data_points));
+    }
+
+    chart
+}
+
+#[function_component(SkillPieChart)]
+pub fn skill_pie_chart(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+    let group_morphs = props.group_morphs;
+
+    let name = compute_title(&partitions, "Top 75 Most Used Skills (All Patches)");
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (chart_data, chart_colors) = if group_morphs {
+            crate::chart_data::top_n_skill_groups_chart_vectors(&master_table, &filter, top_n)
+        } else {
+            crate::chart_data::top_n_skills_chart_vectors(&master_table, &filter, top_n)
+        };
+
+        let chart = build_chart_from_data(chart_data, chart_colors);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round())}>{name}</div>
+            <div id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[function_component(SetPieChart)]
+pub fn set_pie_chart(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = compute_title(&partitions, "Top 50 Most Used Sets (All Patches)");
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (chart_data, chart_colors) =
+            crate::chart_data::top_n_sets_chart_vectors(&master_table, &filter, top_n);
+
+        let chart = build_chart_from_data(chart_data, chart_colors);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.5em;", (width as f32).sqrt().round())}>{name}</div>
+            <div id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[function_component(LargeSkillPieChart)]
+pub fn large_skill_pie_chart(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+    let group_morphs = props.group_morphs;
+
+    let name = compute_title(&partitions, "Top 75 Most Used Skills (All Patches)");
+    let subtitle = "data from top 100 parses on every boss in every patch on esologs, normalised between patches".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (chart_data, chart_colors) = if group_morphs {
+            crate::chart_data::top_n_skill_groups_normalised_chart_vectors(&master_table, &filter, top_n)
+        } else {
+            crate::chart_data::top_n_skills_normalised_chart_vectors(&master_table, &filter, top_n)
+        };
+
+        let chart = build_large_chart_from_data(chart_data, chart_colors);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 2em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[function_component(LargeSetPieChart)]
+pub fn large_set_pie_chart(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = compute_title(&partitions, "Top 50 Most Used Sets (All Patches)");
+    let subtitle = "data from top 100 parses on every boss in every patch on esologs, normalised between patches".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (chart_data, chart_colors) =
+            crate::chart_data::top_n_sets_normalised_chart_vectors(&master_table, &filter, top_n);
+
+        let chart = build_large_chart_from_data(chart_data, chart_colors);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 2em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[function_component(LargeSetsBarGraph)]
+pub fn large_bar_graph(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = format!("Percentage of Boss Parses Using Each Set (U{})", partition_to_update_id(partitions[0]));
+    let subtitle = "data from top 100 parses on every boss this patch".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (chart_data, chart_colors) =
+            crate::chart_data::top_n_sets_percentage_chart_vectors(&master_table, &filter, top_n);
+
+        let chart = build_large_bar_graph_from_data(chart_data, chart_colors);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[function_component(LargeSkillsBarGraph)]
+pub fn large_bar_graph(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+    let group_morphs = props.group_morphs;
+    let on_select = props.on_select.clone();
+
+    let counted = if group_morphs { "Base Skill" } else { "Skill" };
+    let name = format!("Percentage of Boss Parses Using Each {} (U{})", counted, partition_to_update_id(partitions[0]));
+    let subtitle = "data from top 100 parses on every boss this patch".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let chart = if group_morphs {
+            build_large_bar_graph_skill_groups_from_data(crate::chart_data::top_n_skill_groups_percentage_chart_vectors(&master_table, &filter, top_n))
+        } else {
+            let (chart_data, chart_colors) =
+                crate::chart_data::top_n_skills_percentage_chart_vectors(&master_table, &filter, top_n);
+            build_large_bar_graph_skills_from_data(chart_data, chart_colors)
+        };
+
+        let echarts = renderer.render(&chart_id_clone, &chart).unwrap();
+
+        if let Some(on_select) = on_select.filter(|_| !group_morphs) {
+            let skill_ids: Vec<u16> = crate::data::percent_players_with_skills(&master_table, &filter)
+                .into_iter()
+                .take(top_n)
+                .map(|(skill, _)| skill.id)
+                .collect();
+            on_click(&echarts, move |index| {
+                if let Some(id) = skill_ids.get(index) {
+                    on_select.emit(*id);
+                }
+            });
+        }
+
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+pub fn build_weekly_usage_line_chart(weeks: Vec<String>, series: Vec<(String, Color, Vec<f64>)>) -> Chart {
+    let mut chart = Chart::new()
+        .x_axis(Axis::new().type_(AxisType::Category).data(weeks).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16)))
+        .y_axis(Axis::new().type_(AxisType::Value).name("Percent of Parses").name_location(NameLocation::Center).name_gap(45).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16).formatter("{value}%")))
+        .tooltip(Tooltip::new().trigger(Trigger::Axis).background_color("#282c38".to_string()).border_width(2))
+        .legend(Legend::new().type_(LegendType::Scroll).top("top").text_style(TextStyle::new().color(Color::Value("#FFFFFF".to_string())).font_family("TF2Build").font_size(16)));
+
+    for (name, color, values) in series {
+        let values: Vec<f64> = values.iter().map(|v| (v * 10.0).round() / 10.0).collect();
+        chart = chart.series(
+            Line::new()
+                .name(name)
+                .data(values)
+                .item_style(ItemStyle::new().color(color.clone()))
+                .line_style(LineStyle::new().width(3).color(color)),
+        );
+    }
+
+    chart
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct WeeklyUsageProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    pub partitions: Vec<u8>,
+    /// bosses, trials, ST/AOE and rankings to count, over `partitions`
+    #[prop_or_default]
+    pub filter: RowFilter,
+    pub targets: Vec<UsageTarget>,
+    pub chart_id: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[function_component(WeeklyUsageLineChart)]
+pub fn weekly_usage_line_chart(props: &WeeklyUsageProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let targets = props.targets.clone();
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = format!("Weekly Usage Within {}", compute_title(&partitions, "All Patches"));
+    let subtitle = if filter.dates.start.is_some() {
+        "percent of top 100 parses each week, counted from the start of the date range".to_string()
+    } else {
+        "percent of top 100 parses each week, weeks starting on Monday".to_string()
+    };
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (weeks, series) = crate::data::weekly_usage(&master_table, &filter, &targets);
+
+        let labels: Vec<String> = weeks
+            .iter()
+            .enumerate()
+            .map(|(i, start)| format!("Week {} ({})", i + 1, start))
+            .collect();
+        let series: Vec<(String, Color, Vec<f64>)> = targets
+            .iter()
+            .zip(series)
+            .map(|(target, values)| {
+                let (label, colour) = crate::chart_data::usage_target_label(&master_table, *target);
+                (label, colour, values)
+            })
+            .collect();
+
+        let chart = build_weekly_usage_line_chart(labels, series);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TrendProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    /// bosses, trials, ST/AOE and rankings to count, in every partition
+    #[prop_or_default]
+    pub filter: RowFilter,
+    pub targets: Vec<UsageTarget>,
+    pub chart_id: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[function_component(TrendLineChart)]
+pub fn trend_line_chart(props: &TrendProps) -> Html {
+    let master_table = props.master_table.clone();
+    let filter = props.filter.clone();
+    let targets = props.targets.clone();
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = "Usage Across Every Patch".to_string();
+    let subtitle = "percent of top 100 parses in each patch".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+        let (partitions, series) = crate::data::partition_usage(&master_table, &filter, &targets);
+
+        let labels: Vec<String> = partitions
+            .iter()
+            .map(|p| format!("U{}", partition_to_update_id(*p)))
+            .collect();
+        let series: Vec<(String, Color, Vec<f64>)> = targets
+            .iter()
+            .zip(series)
+            .map(|(target, values)| {
+                let (label, colour) = crate::chart_data::usage_target_label(&master_table, *target);
+                (label, colour, values)
+            })
+            .collect();
+
+        let chart = build_weekly_usage_line_chart(labels, series);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+const SHARE_COLOURS: [&str; 9] = [
+    "#CF6A32", "#476291", "#D32CE6", "#38F3AB", "#4B69FF", "#FFD700", "#EB4B4B", "#8847FF", "#B0C3D9",
+];
+
+pub fn build_stacked_share_bar_chart(categories: Vec<String>, series: Vec<(String, Color, Vec<f64>)>) -> Chart {
+    let mut chart = Chart::new()
+        .x_axis(Axis::new().type_(AxisType::Category).data(categories).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16)))
+        .y_axis(Axis::new().type_(AxisType::Value).max(100).name("Percent of Parses").name_location(NameLocation::Center).name_gap(45).name_text_style(TextStyle::new().font_family("TF2Build").font_size(24).color("#fff")).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(16).formatter("{value}%")))
+        .tooltip(Tooltip::new().trigger(Trigger::Axis).background_color("#282c38".to_string()).border_width(2))
+        .legend(Legend::new().type_(LegendType::Scroll).top("top").text_style(TextStyle::new().color(Color::Value("#FFFFFF".to_string())).font_family("TF2Build").font_size(16)));
+
+    for (name, color, values) in series {
+        let values: Vec<f64> = values.iter().map(|v| (v * 10.0).round() / 10.0).collect();
+        chart = chart.series(
+            Bar::new()
+                .name(name)
+                .stack("share")
+                .data(values)
+                .item_style(ItemStyle::new().color(color)),
+        );
+    }
+
+    chart
+}
+
+/// One series per top key with its share in each group, plus "Other" for the
+/// rest, ready for `build_stacked_share_bar_chart`.
+fn stacked_share_series<K: PartialEq>(groups: &[Vec<(K, f64)>], top: &[K], label: impl Fn(&K) -> String) -> Vec<(String, Color, Vec<f64>)> {
+    let mut series: Vec<(String, Color, Vec<f64>)> = top
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let values = groups
+                .iter()
+                .map(|shares| shares.iter().find(|(k, _)| k == key).map(|(_, pct)| *pct).unwrap_or(0.0))
+                .collect();
+            let colour = Color::from(SHARE_COLOURS[i % SHARE_COLOURS.len()]);
+            (label(key), colour, values)
+        })
+        .collect();
+
+    let other: Vec<f64> = groups
+        .iter()
+        .map(|shares| shares.iter().filter(|(k, _)| !top.contains(k)).map(|(_, pct)| pct).sum())
+        .collect();
+    series.push(("Other".to_string(), Color::from("#5E6270"), other));
+
+    series
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct WeaponLoadoutProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    pub partitions: Vec<u8>,
+    /// bosses, trials, ST/AOE and rankings to count, over `partitions`
+    #[prop_or_default]
+    pub filter: RowFilter,
+    pub top_n: usize,
+    /// one bar per boss instead of one per partition
+    #[prop_or_default]
+    pub by_boss: bool,
+    pub chart_id: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[function_component(WeaponLoadoutBarChart)]
+pub fn weapon_loadout_bar_chart(props: &WeaponLoadoutProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let by_boss = props.by_boss;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = if by_boss {
+        format!("Weapon Loadouts Per Boss Within {}", compute_title(&partitions, "All Patches"))
+    } else {
+        "Weapon Loadouts By Patch".to_string()
+    };
+    let subtitle = "front bar / back bar, from the gear icons of the top 100 parses".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+
+        let groups = if by_boss {
+            crate::data::weapon_loadouts_per_boss(&master_table, &filter)
+        } else {
+            crate::data::weapon_loadouts_per_partition(&master_table, &filter)
+        };
+        let top_loadouts: Vec<crate::data::WeaponLoadout> = crate::data::percent_players_with_weapon_loadouts(&master_table, &filter)
+            .into_iter()
+            .take(top_n)
+            .map(|(loadout, _)| loadout)
+            .collect();
+
+        let (ids, shares): (Vec<u8>, Vec<_>) = groups.into_iter().unzip();
+        let categories: Vec<String> = ids
+            .iter()
+            .map(|id| if by_boss { boss_to_boss_name(*id) } else { format!("U{}", partition_to_update_id(*id)) })
+            .collect();
+        let series = stacked_share_series(&shares, &top_loadouts, |l| crate::data::weapon_loadout_label(*l));
+
+        let chart = build_stacked_share_bar_chart(categories, series);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ArmourWeightProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    pub partitions: Vec<u8>,
+    /// bosses, trials, ST/AOE and rankings to count, over `partitions`
+    #[prop_or_default]
+    pub filter: RowFilter,
+    pub top_n: usize,
+    /// one bar per class instead of one per partition
+    #[prop_or_default]
+    pub by_class: bool,
+    pub chart_id: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[function_component(ArmourWeightBarChart)]
+pub fn armour_weight_bar_chart(props: &ArmourWeightProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let by_class = props.by_class;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = if by_class {
+        format!("Armour Weights Per Class Within {}", compute_title(&partitions, "All Patches"))
+    } else {
+        "Armour Weights By Patch".to_string()
+    };
+    let subtitle = "body pieces of each weight, from the gear icons of the top 100 parses".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+
+        let (categories, shares): (Vec<String>, Vec<_>) = if by_class {
+            crate::data::armour_weights_per_class(&master_table, &filter).into_iter().unzip()
+        } else {
+            crate::data::armour_weights_per_partition(&master_table, &filter)
+                .into_iter()
+                .map(|(p, shares)| (format!("U{}", partition_to_update_id(p)), shares))
+                .unzip()
+        };
+        let top_splits: Vec<crate::data::ArmourWeights> = crate::data::percent_players_with_armour_weights(&master_table, &filter)
+            .into_iter()
+            .take(top_n)
+            .map(|(weights, _)| weights)
+            .collect();
+
+        let series = stacked_share_series(&shares, &top_splits, |w| w.label());
+        let chart = build_stacked_share_bar_chart(categories, series);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ClassShareProps {
+    pub master_table: Rc<crate::data::MasterTable>,
+    pub partitions: Vec<u8>,
+    /// bosses, trials, ST/AOE and rankings to count, over `partitions`
+    #[prop_or_default]
+    pub filter: RowFilter,
+    pub top_n: usize,
+    /// one bar per boss instead of one per partition
+    #[prop_or_default]
+    pub by_boss: bool,
+    /// class combinations instead of the main class
+    #[prop_or_default]
+    pub combos: bool,
+    pub chart_id: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[function_component(ClassShareBarChart)]
+pub fn class_share_bar_chart(props: &ClassShareProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let by_boss = props.by_boss;
+    let combos = props.combos;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let counted = if combos { "Class Combinations" } else { "Classes" };
+    let name = if by_boss {
+        format!("{} Per Boss Within {}", counted, compute_title(&partitions, "All Patches"))
+    } else {
+        format!("{} By Patch", counted)
+    };
+    let subtitle = if combos {
+        "main class first, then any subclassed skill lines, from the skills of the top 100 parses".to_string()
+    } else {
+        "class with the most skills slotted, from the skills of the top 100 parses".to_string()
+    };
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+
+        let category = |id: u8| if by_boss { boss_to_boss_name(id) } else { format!("U{}", partition_to_update_id(id)) };
+
+        let (categories, series) = if combos {
+            let groups = if by_boss {
+                crate::data::class_combos_per_boss(&master_table, &filter)
+            } else {
+                crate::data::class_combos_per_partition(&master_table, &filter)
+            };
+            let top_combos: Vec<crate::data::ClassCombo> = crate::data::percent_players_with_class_combos(&master_table, &filter)
+                .into_iter()
+                .take(top_n)
+                .map(|(classes, _)| classes)
+                .collect();
+
+            let (ids, shares): (Vec<u8>, Vec<_>) = groups.into_iter().unzip();
+            let series = stacked_share_series(&shares, &top_combos, |c| class_combo_label(c));
+            (ids.into_iter().map(category).collect(), series)
+        } else {
+            let groups = if by_boss {
+                crate::data::classes_per_boss(&master_table, &filter)
+            } else {
+                crate::data::classes_per_partition(&master_table, &filter)
+            };
+
+            let (ids, shares): (Vec<u8>, Vec<_>) = groups.into_iter().unzip();
+            let mut series = stacked_share_series(&shares, &Class::ALL, |c| c.name().to_string());
+            for ((_, colour, _), class) in series.iter_mut().zip(Class::ALL) {
+                *colour = colour_from_class(class);
+            }
+            (ids.into_iter().map(category).collect(), series)
+        };
+
+        let chart = build_stacked_share_bar_chart(categories, series);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+/// Square heatmap over `labels`, with `cells` as (column, row, percent). The
+/// first label is drawn in the top row.
+pub fn build_co_occurrence_heatmap(labels: Vec<String>, cells: Vec<(usize, usize, f64)>) -> Chart {
+    let last = labels.len().saturating_sub(1);
+    let data: Vec<DataFrame> = cells
+        .into_iter()
+        .map(|(x, y, pct)| [x as f64, (last - y) as f64, (pct * 10.0).round() / 10.0].map(DataPoint::from).to_vec())
+        .collect();
+    let row_labels: Vec<String> = labels.iter().rev().cloned().collect();
+
+    let tooltip_body = format!(
+        r#"
+            const columns = {:?};
+            const rows = {:?};
+            return `
+            <div style="color: #fff; font-family: "TF2Build";">
+                ${{params.value[2].toFixed(1)}}% of ${{rows[params.value[1]]}} parses also wear ${{columns[params.value[0]]}}
+            </div>
+            `
+        "#,
+        labels, row_labels
+    );
+
+    Chart::new()
+        .grid(Grid::new().left("18%").right("4%").top("2%").bottom("22%"))
+        .x_axis(Axis::new().type_(AxisType::Category).data(labels.clone()).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(14).rotate(45)))
+        .y_axis(Axis::new().type_(AxisType::Category).data(row_labels).axis_label(AxisLabel::new().color("#fff").font_family("TF2Build").font_size(14)))
+        .visual_map(VisualMap::new().min(0).max(100).calculable(true).orient(Orient::Horizontal).left("center").bottom("0%"))
+        .tooltip(
+            Tooltip::new()
+                .trigger(Trigger::Item)
+                .background_color("#282c38".to_string())
+                .border_width(2)
+                .formatter(JsFunction::new_with_args("params", &tooltip_body)),
+        )
+        .series(Heatmap::new().name("Worn together").label(Label::new().show(true).color("#fff").font_family("TF2Build")).data(data))
+}
+
+#[function_component(SetCoOccurrenceHeatmap)]
+pub fn set_co_occurrence_heatmap(props: &PieChartProps) -> Html {
+    let master_table = props.master_table.clone();
+    let partitions = props.partitions.clone();
+    let filter = props.filter.with_partitions(&partitions);
+    let top_n = props.top_n;
+    let chart_id = props.chart_id.clone();
+    let chart_id_clone = chart_id.clone();
+    let width = props.width;
+    let height = props.height;
+
+    let name = format!("Sets Worn Together Within {}", compute_title(&partitions, "All Patches"));
+    let subtitle = "percent of the parses wearing the row's set that also wear the column's set".to_string();
+
+    let render_task = yew_hooks::use_async(async move {
+        let renderer = WasmRenderer::new(width, height);
+
+        let top_sets: Vec<crate::data::ItemSet> = crate::data::percent_players_with_sets(&master_table, &filter)
+            .into_iter()
+            .take(top_n)
+            .map(|(set, _)| set)
+            .collect();
+        let position = |set: &crate::data::ItemSet| top_sets.iter().position(|s| s.id == set.id);
+
+        let cells: Vec<(usize, usize, f64)> = crate::data::set_co_occurrence(&master_table, &filter)
+            .into_iter()
+            .filter_map(|pair| Some((position(&pair.second)?, position(&pair.first)?, pair.second_given_first)))
+            .collect();
+        let labels: Vec<String> = top_sets.iter().map(|s| s.name.clone()).collect();
+
+        let chart = build_co_occurrence_heatmap(labels, cells);
+
+        renderer.render(&chart_id_clone, &chart).unwrap();
+        Ok::<(), ()>(())
+    });
+
+    {
+        let render_task = render_task.clone();
+        use_effect_with((), move |_| {
+            render_task.run();
+            || ()
+        });
+    }
+
+    html! {
+        <div style="display: flex; flex-direction: column; align-items: center; color: #fff; margin: 1em; user-select: none;">
+            <div style={format!("font-size: {}px; margin-bottom: 0.25em;", (width as f32).sqrt().round() * 1.2)}>{name}</div>
+            <div style={format!("font-size: {}px; margin-bottom: 1em;", (width as f32).sqrt().round() * 0.5)}>{subtitle}</div>
+            <div style="margin:2em;" id={chart_id.clone()} />
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TopPlayersProps {
+    pub rows: Vec<(u64, Player)>,
+}
+
+
+#[function_component(TopPlayersTable)]
+pub fn top_players_table(props: &TopPlayersProps) -> Html {
+    html! {
+        <table style="margin-left: auto; margin-right: auto; margin-top: 1em; font-size: 2em;">
+            <thead style="color: #fff; text-align: center;">
+                <tr>
+                    // <th style="width: 25%">{"Rank"}</th>
+                    <th style="width: 60%">{"Player"}</th>
+                    <th style="width: 40%">{"#1 Rankings"}</th>
+                </tr>
+            </thead>
+            <tbody style="color: #fff; text-align: center;">
+                { for props.rows.iter().map(|(count, player)|
+                    html! {
+                        <tr>
+                            // <td> { format!("{}.", i + 1) } </td>
+                            <td> { hodor_name_to_html(if player.text.is_empty() {&player.name} else { &player.text }) } </td>
+                            <td> { count } </td>
+                        </tr>
+                    }
+                ) }
+            </tbody>
+        </table>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SkillCompanionsProps {
+    pub skill_name: String,
+    pub rows: Vec<SkillCompanion>,
+}
+
+#[function_component(SkillCompanionsTable)]
+pub fn skill_companions_table(props: &SkillCompanionsProps) -> Html {
+    html! {
+        <table style="margin-left: auto; margin-right: auto; margin-top: 1em; font-size: 1.5em;">
+            <thead style="color: #fff; text-align: center;">
+                <tr>
+                    <th style="width: 40%">{format!("Usually Slotted With {}", props.skill_name)}</th>
+                    <th style="width: 20%">{"With It"}</th>
+                    <th style="width: 20%">{"Overall"}</th>
+                    <th style="width: 20%">{"Lift"}</th>
+                </tr>
+            </thead>
+            <tbody style="color: #fff; text-align: center;">
+                { for props.rows.iter().map(|companion|
+                    html! {
+                        <tr>
+                            <td> { companion.skill.display_name.clone().unwrap_or_else(|| companion.skill.name.clone()) } </td>
+                            <td> { format!("{:.1}%", companion.with_percent) } </td>
+                            <td> { format!("{:.1}%", companion.baseline_percent) } </td>
+                            <td> { format!("{:.2}x", companion.lift) } </td>
+                        </tr>
+                    }
+                ) }
+            </tbody>
+        </table>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SetCombosProps {
+    pub rows: Vec<(SetCombo, f64)>,
+}
+
+#[function_component(SetCombosTable)]
+pub fn set_combos_table(props: &SetCombosProps) -> Html {
+    html! {
+        <table style="margin-left: auto; margin-right: auto; margin-top: 1em; font-size: 1.5em;">
+            <thead style="color: #fff; text-align: center;">
+                <tr>
+                    <th style="width: 80%">{"Full Set Combination"}</th>
+                    <th style="width: 20%">{"Parses"}</th>
+                </tr>
+            </thead>
+            <tbody style="color: #fff; text-align: center;">
+                { for props.rows.iter().map(|(combo, pct)|
+                    html! {
+                        <tr>
+                            <td> { set_combo_label(combo) } </td>
+                            <td> { format!("{:.1}%", pct) } </td>
+                        </tr>
+                    }
+                ) }
+            </tbody>
+        </table>
+    }
+}
\ No newline at end of file
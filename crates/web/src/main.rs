@@ -1,23 +1,36 @@
 use yew::{Callback, Html, function_component, html, use_memo, use_state};
 use yew_icons::{Icon, IconId};
 
-use crate::{players::PlayersPage, sets::SetsPage, skills::SkillsPage};
+use parselist::data;
 
-mod data;
+use crate::{builds::BuildsPage, classes::ClassesPage, players::PlayersPage, sets::SetsPage, skills::SkillsPage};
+
+mod builds;
+mod chart_data;
+mod classes;
+mod filters;
 mod graph;
 mod players;
 mod skills;
 mod sets;
 
+const MASTER_TABLE_BYTES: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/master_table.bin"));
+
+fn load_master_table() -> data::MasterTable {
+    data::decode_master_table(MASTER_TABLE_BYTES).expect("Failed to decode master table")
+}
+
 enum Page {
     Players,
     Sets,
-    Skills
+    Skills,
+    Classes,
+    Builds
 }
 
 #[function_component(App)]
 pub fn app() -> Html {
-    let master_table = use_memo((), |_| data::load_master_table());
+    let master_table = use_memo((), |_| load_master_table());
     let page = use_state(|| Page::Players);
 
     let on_nav = {
@@ -28,6 +41,8 @@ pub fn app() -> Html {
     let content = match *page {
         Page::Skills => html! { <SkillsPage master_table={master_table.clone()} /> },
         Page::Sets => html! { <SetsPage master_table={master_table.clone()} /> },
+        Page::Classes => html! { <ClassesPage master_table={master_table.clone()} /> },
+        Page::Builds => html! { <BuildsPage master_table={master_table.clone()} /> },
         Page::Players => html! { <PlayersPage master_table={master_table.clone()} /> },
     };
 
@@ -42,6 +57,8 @@ pub fn app() -> Html {
                         <button onclick={Callback::from({let on_nav = on_nav.clone(); move |_| on_nav.emit(Page::Players)})} style={button_style.clone()}>{"Players"}</button>
                         <button onclick={Callback::from({let on_nav = on_nav.clone(); move |_| on_nav.emit(Page::Skills)})} style={button_style.clone()}>{"Skills"}</button>
                         <button onclick={Callback::from({let on_nav = on_nav.clone(); move |_| on_nav.emit(Page::Sets)})} style={button_style.clone()}>{"Sets"}</button>
+                        <button onclick={Callback::from({let on_nav = on_nav.clone(); move |_| on_nav.emit(Page::Classes)})} style={button_style.clone()}>{"Classes"}</button>
+                        <button onclick={Callback::from({let on_nav = on_nav.clone(); move |_| on_nav.emit(Page::Builds)})} style={button_style.clone()}>{"Builds"}</button>
                     </div>
                 </div>
             </div>
@@ -64,5 +81,4 @@ pub fn app() -> Html {
 
 fn main() {
     yew::Renderer::<App>::new().render();
-    // data::process_data_into_master_table_serialized()
 }
@@ -3,20 +3,23 @@ use std::rc::Rc;
 use yew::prelude::*;
 use web_sys::{KeyboardEvent, InputEvent};
 
+use parselist::filter::RowFilter;
 
-use crate::{data::{self, MasterTable, TableRow, boss_to_boss_name, partition_to_name, top_n_players_by_top_k_count}, graph::TopPlayersTable};
+use crate::{data::{self, MasterTable, TableRow, boss_to_boss_name, character_name, partition_to_name, player_characters, top_n_players_by_top_k_count}, graph::TopPlayersTable};
 
-fn create_player_row(_master: &MasterTable, row: TableRow) -> Html {
+fn create_player_row(master: &MasterTable, row: TableRow) -> Html {
     let dps = row.dps;
     let boss = boss_to_boss_name(row.boss_id);
     let ranking = row.ranking;
     let aoe_st = if row.boss {"ST"} else {"AOE"};    
+    let character = character_name(master, row.character_id).unwrap_or_default().to_string();
 
     html!{
         <div style="display: flex; gap: 10px; flex-direction: row; width: max-content; font-size: 1.5em;">
             <span>{format!("{}.", ranking)}</span>
             <span>{format!("{:.1}k {}", dps as f32 / 1000.0, aoe_st)}</span>
             <span>{boss}</span>
+            <span style="color: #B2B2B2;">{character}</span>
         </div>
     }
 }
@@ -111,9 +114,9 @@ pub fn players_page(props: &PlayersPageProps) -> Html {
 
     let selected_player = use_state(|| None::<u32>);
     let search_open = use_state(|| false);
-    let search_query = use_state(|| String::new());
+    let search_query = use_state(String::new);
 
-    let matches_ref = use_mut_ref(|| Vec::<(usize, String)>::new());
+    let matches_ref = use_mut_ref(Vec::<(usize, String)>::new);
 
     let matches = {
         let query = (*search_query).to_lowercase().trim_matches('@').to_owned();
@@ -151,7 +154,7 @@ pub fn players_page(props: &PlayersPageProps) -> Html {
 
         Callback::from(move |idx: usize| {
             if let Some(player) = master_table.players.get(idx) {
-                selected_player.set(Some(player.id.clone()));
+                selected_player.set(Some(player.id));
             }
             search_open.set(false);
             search_query.set(String::new());
@@ -197,10 +200,17 @@ pub fn players_page(props: &PlayersPageProps) -> Html {
         let player_name_html = if player_text.is_empty() {
             html! { &master_table.players[player_id as usize - 1].name }
         } else {
-            hodor_name_to_html(&player_text)
+            hodor_name_to_html(player_text)
         };
 
 
+        let characters = player_characters(&master_table, player_id, &RowFilter::default())
+            .into_iter()
+            .map(|(name, count)| format!("{} ({})", name, count))
+            .collect::<Vec<_>>()
+            .join(" \u{00B7} ");
+
+
         for r in rows {
             if r.ranking > 25 { continue; }
             if Some(r.partition_id) != last_partition {
@@ -228,6 +238,7 @@ pub fn players_page(props: &PlayersPageProps) -> Html {
         html! {
             <div style="display: flex; flex-direction: column; align-items: center; gap: 5px; color: #fff;">
             <div style="font-size: 5em;">{ player_name_html }</div>
+            <div style="font-size: 1.5em; color: #B2B2B2; margin-bottom: 0.5em;">{ characters }</div>
                 { for children }
             </div>
         }
@@ -268,13 +279,13 @@ pub fn players_page(props: &PlayersPageProps) -> Html {
                     </ul>
                 }
             </div>
-            if let Some(_) = *selected_player {
+            if selected_player.is_some() {
                 <div style="flex: 1 1 30em;">
                     { player_data }
                 </div>
             } else {
                 <TopPlayersTable
-                    rows={top_n_players_by_top_k_count(&master_table, &[], 101, 1)}
+                    rows={top_n_players_by_top_k_count(&master_table, &RowFilter::default(), 101, 1)}
                 />
             }
         </div>
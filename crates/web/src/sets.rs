@@ -0,0 +1,195 @@
+use std::rc::Rc;
+
+use yew::{Callback, Html, Properties, function_component, html, use_state};
+
+use parselist::filter::RowFilter;
+
+use crate::{data::{UsageTarget, percent_players_with_sets, top_n_set_combos}, filters::{FilterControls, UsagePicker}, graph::{ArmourWeightBarChart, LargeSetsBarGraph, LargeSetPieChart, SetCoOccurrenceHeatmap, SetCombosTable, SetPieChart, TrendLineChart, WeaponLoadoutBarChart, WeeklyUsageLineChart}};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SetsPageProps {
+pub master_table: Rc<crate::data::MasterTable>,
+}
+
+
+#[function_component(SetsPage)]
+pub fn sets_page(props: &SetsPageProps) -> Html {
+    let master_table = props.master_table.clone();
+    let filter = use_state(RowFilter::default);
+    let filter_key = format!("{:?}", *filter);
+
+    let on_filter = {
+        let filter = filter.clone();
+        Callback::from(move |f: RowFilter| filter.set(f))
+    };
+
+    // every set ever worn, most worn first
+    let trend_options: Vec<(UsageTarget, String)> = percent_players_with_sets(&master_table, &RowFilter::default())
+        .into_iter()
+        .map(|(set, _)| (UsageTarget::Set(set.id), set.name))
+        .collect();
+
+    let trend_targets = {
+        let master_table = master_table.clone();
+        use_state(move || {
+            percent_players_with_sets(&master_table, &RowFilter::default().with_partitions(&[27]))
+                .into_iter()
+                .take(3)
+                .map(|(set, _)| UsageTarget::Set(set.id))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let on_trend_targets = {
+        let trend_targets = trend_targets.clone();
+        Callback::from(move |targets: Vec<UsageTarget>| trend_targets.set(targets))
+    };
+
+    let set_charts: Html = (1..28)
+    .map(|i| {
+        html! {
+            <SetPieChart
+                key={format!("topsets_{}_{}", i, filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![i]}
+                top_n={12}
+                chart_id={format!("topsets_{}", i)}
+                width={500}
+                height={300}
+            />
+        }
+    }).rev().collect();
+
+    let weekly_targets: Vec<UsageTarget> = percent_players_with_sets(&master_table, &filter.with_partitions(&[27]))
+        .into_iter()
+        .take(8)
+        .map(|(set, _)| UsageTarget::Set(set.id))
+        .collect();
+
+    let set_combos = top_n_set_combos(&master_table, &filter.with_partitions(&[27]), 20);
+
+
+    html! {
+        <div style="width: 100%; display: flex; flex-direction: column; gap: 2rem; align-items: center; color: #fff;">
+            // <LargeSetPieChart
+            //     master_table={master_table.clone()}
+            //     partitions={vec![27]}
+            //     top_n={24}
+            //     chart_id={"topsets_latest".to_string()}
+            //     width={1500}
+            //     height={900}
+            // />
+
+            <FilterControls master_table={master_table.clone()} filter={(*filter).clone()} on_change={on_filter} />
+
+            <LargeSetsBarGraph
+                key={format!("topsets_bar_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                top_n={24}
+                chart_id={"topsets_bar".to_string()}
+                width={1500}
+                height={900}
+            />
+
+            <SetCoOccurrenceHeatmap
+                key={format!("setpairs_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                top_n={16}
+                chart_id={"setpairs".to_string()}
+                width={1500}
+                height={1100}
+            />
+
+            <div style="font-size: 2rem; margin: 1rem; font-weight: bold; user-select: none; text-align: center;">{"Most Common Full Set Combinations This Patch"}</div>
+            <SetCombosTable rows={set_combos} />
+
+            <WeeklyUsageLineChart
+                key={format!("weeklysets_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                targets={weekly_targets}
+                chart_id={"weeklysets".to_string()}
+                width={1500}
+                height={600}
+            />
+
+            <WeaponLoadoutBarChart
+                key={format!("weaponloadouts_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![]}
+                top_n={8}
+                chart_id={"weaponloadouts".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <WeaponLoadoutBarChart
+                key={format!("weaponloadouts_bosses_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                top_n={8}
+                by_boss={true}
+                chart_id={"weaponloadouts_bosses".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <ArmourWeightBarChart
+                key={format!("armourweights_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![]}
+                top_n={8}
+                chart_id={"armourweights".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <ArmourWeightBarChart
+                key={format!("armourweights_classes_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                top_n={8}
+                by_class={true}
+                chart_id={"armourweights_classes".to_string()}
+                width={1500}
+                height={700}
+            />
+
+            <UsagePicker options={trend_options} selected={(*trend_targets).clone()} placeholder={"Add a set to the trend".to_string()} on_change={on_trend_targets} />
+            <TrendLineChart
+                key={format!("settrend_{:?}_{}", *trend_targets, filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                targets={(*trend_targets).clone()}
+                chart_id={"settrend".to_string()}
+                width={1500}
+                height={600}
+            />
+
+            <div style="font-size: 2rem; margin: 1rem; font-weight: bold; user-select: none; text-align: center;">{"Top 12 Most Frequently Used Parse Sets By Patch"}</div>
+            <div style="display: flex; flex-wrap: wrap; gap: 1rem; justify-content: center; width: 100%;">
+                { set_charts }
+            </div>
+            <LargeSetPieChart
+                key={format!("topsets_all_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![]}
+                top_n={50}
+                chart_id={"topsets_all".to_string()}
+                width={1500}
+                height={900}
+            />
+        </div>
+    }
+}
\ No newline at end of file
@@ -0,0 +1,175 @@
+use std::rc::Rc;
+
+use yew::{Callback, Html, Properties, function_component, html, use_state};
+
+use parselist::filter::RowFilter;
+
+use crate::{data::{UsageTarget, percent_players_with_skills, skill_companions}, filters::{FilterControls, UsagePicker}, graph::{LargeSkillPieChart, LargeSkillsBarGraph, SkillCompanionsTable, SkillPieChart, TrendLineChart, WeeklyUsageLineChart}};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SkillsPageProps {
+pub master_table: Rc<crate::data::MasterTable>,
+}
+
+
+#[function_component(SkillsPage)]
+pub fn skills_page(props: &SkillsPageProps) -> Html {
+let master_table = props.master_table.clone();
+    let filter = use_state(RowFilter::default);
+    let filter_key = format!("{:?}", *filter);
+
+    let on_filter = {
+        let filter = filter.clone();
+        Callback::from(move |f: RowFilter| filter.set(f))
+    };
+
+    // every skill ever slotted, most slotted first
+    let trend_options: Vec<(UsageTarget, String)> = percent_players_with_skills(&master_table, &RowFilter::default())
+        .into_iter()
+        .map(|(skill, _)| (UsageTarget::Skill(skill.id), skill.display_name.unwrap_or(skill.name)))
+        .collect();
+
+    let trend_targets = {
+        let master_table = master_table.clone();
+        use_state(move || {
+            percent_players_with_skills(&master_table, &RowFilter::default().with_partitions(&[27]))
+                .into_iter()
+                .take(3)
+                .map(|(skill, _)| UsageTarget::Skill(skill.id))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let on_trend_targets = {
+        let trend_targets = trend_targets.clone();
+        Callback::from(move |targets: Vec<UsageTarget>| trend_targets.set(targets))
+    };
+
+    let group_morphs = use_state(|| false);
+
+    let toggle_group_morphs = {
+        let group_morphs = group_morphs.clone();
+        Callback::from(move |_| group_morphs.set(!*group_morphs))
+    };
+
+    let selected_skill = use_state(|| None::<u16>);
+
+    let on_select_skill = {
+        let selected_skill = selected_skill.clone();
+        Callback::from(move |id: u16| selected_skill.set(Some(id)))
+    };
+
+    let toggle_style = "background-color: #15171fff; font-family: 'TF2Build'; color: #fff; border: none; padding: 0.25em 0.5em; outline: none; font-size: 1.5em; border-radius: 0.25em; cursor: pointer;";
+    let toggle_label = if *group_morphs { "Showing Base Skills (Morphs Grouped)" } else { "Showing Morphs Separately" };
+
+    let skill_charts: Html = (1..28)
+    .map(|i| {
+        html! {
+            <SkillPieChart
+                key={format!("topskills_{}_{}_{}", i, *group_morphs, filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![i]}
+                top_n={12}
+                chart_id={format!("topskills_{}", i)}
+                width={500}
+                height={300}
+                group_morphs={*group_morphs}
+            />
+        }
+    }).rev().collect();
+
+    let weekly_targets: Vec<UsageTarget> = percent_players_with_skills(&master_table, &filter.with_partitions(&[27]))
+        .into_iter()
+        .take(8)
+        .map(|(skill, _)| UsageTarget::Skill(skill.id))
+        .collect();
+
+    let companions: Html = match *selected_skill {
+        Some(id) => {
+            let skill_name = master_table
+                .skill(id)
+                .map(|s| s.display_name.clone().unwrap_or_else(|| s.name.clone()))
+                .unwrap_or_else(|| format!("Unknown ({})", id));
+            let rows: Vec<_> = skill_companions(&master_table, &filter.with_partitions(&[27]), id, 10)
+                .into_iter()
+                .take(15)
+                .collect();
+            html! { <SkillCompanionsTable skill_name={skill_name} rows={rows} /> }
+        }
+        None if *group_morphs => html! {},
+        None => html! {
+            <div style="font-size: 1.5rem; user-select: none;">{"Click a skill in the chart above to see what it's usually slotted with"}</div>
+        },
+    };
+
+
+    html! {
+        <div style="width: 100%; display: flex; flex-direction: column; gap: 2rem; align-items: center; color: #fff;">
+            // <LargeSkillPieChart
+            //     master_table={master_table.clone()}
+            //     partitions={vec![27]}
+            //     top_n={30}
+            //     chart_id={"topskills_latest".to_string()}
+            //     width={1500}
+            //     height={900}
+            // />
+
+            <FilterControls master_table={master_table.clone()} filter={(*filter).clone()} on_change={on_filter} />
+            <button onclick={toggle_group_morphs} style={toggle_style}>{toggle_label}</button>
+
+            <LargeSkillsBarGraph
+                key={format!("topskills_bar_{}_{}", *group_morphs, filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                top_n={36}
+                chart_id={"topskills_bar".to_string()}
+                width={1500}
+                height={900}
+                group_morphs={*group_morphs}
+                on_select={on_select_skill}
+            />
+
+            { companions }
+
+            <WeeklyUsageLineChart
+                key={format!("weeklyskills_{}", filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![27]}
+                targets={weekly_targets}
+                chart_id={"weeklyskills".to_string()}
+                width={1500}
+                height={600}
+            />
+
+            <UsagePicker options={trend_options} selected={(*trend_targets).clone()} placeholder={"Add a skill to the trend".to_string()} on_change={on_trend_targets} />
+            <TrendLineChart
+                key={format!("skilltrend_{:?}_{}", *trend_targets, filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                targets={(*trend_targets).clone()}
+                chart_id={"skilltrend".to_string()}
+                width={1500}
+                height={600}
+            />
+
+            <div style="font-size: 2rem; margin: 1rem; font-weight: bold; user-select: none; text-align: center;">{"Top 12 Most Frequently Used Parse Skills By Patch"}</div>
+            <div style="display: flex; flex-wrap: wrap; gap: 1rem; justify-content: center; width: 100%;">
+                { skill_charts }
+            </div>
+            <LargeSkillPieChart
+                key={format!("topskills_all_{}_{}", *group_morphs, filter_key)}
+                master_table={master_table.clone()}
+                filter={(*filter).clone()}
+                partitions={vec![]}
+                top_n={75}
+                chart_id={"topskills_all".to_string()}
+                width={1500}
+                height={900}
+                group_morphs={*group_morphs}
+            />
+        </div>
+    }
+}
\ No newline at end of file